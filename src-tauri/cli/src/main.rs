@@ -0,0 +1,231 @@
+//! Plays Muddy Rogue in a terminal, without the Tauri webview.
+//!
+//! Usage: `muddy-cli [--no-color] [--map] [--saves DIR] [--tick MILLIS] [CONTENT_DIR]...`
+//!
+//! Content directories are layered the same way the desktop app loads them.
+//! Besides the game commands, `map` draws the minimap, `map zone` draws every
+//! explored room of the current zone and `quit` exits. The world ticks on a
+//! background thread, printing what happens above the input line.
+
+mod render;
+
+use muddy_engine::ticker::{run_ticks, TickControl, DEFAULT_TICK_RATE};
+use muddy_engine::{Game, Output};
+use render::{render_message, render_minimap};
+use rustyline::error::ReadlineError;
+use rustyline::{DefaultEditor, ExternalPrinter};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const USAGE: &str =
+    "Usage: muddy-cli [--no-color] [--map] [--saves DIR] [--tick MILLIS] [CONTENT_DIR]...";
+const DEFAULT_WIDTH: usize = 80;
+const MINIMAP_RADIUS: i32 = 2;
+
+#[derive(Clone)]
+struct Options {
+    color: bool,
+    auto_map: bool,
+    saves_dir: Option<PathBuf>,
+    tick_rate: Duration,
+    content_dirs: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        color: std::env::var_os("NO_COLOR").is_none(),
+        auto_map: false,
+        saves_dir: None,
+        tick_rate: DEFAULT_TICK_RATE,
+        content_dirs: Vec::new(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-color" => options.color = false,
+            "--map" => options.auto_map = true,
+            "--saves" => {
+                let dir = args.next().ok_or("--saves needs a directory")?;
+                options.saves_dir = Some(PathBuf::from(dir));
+            }
+            "--tick" => {
+                let millis = args.next().ok_or("--tick needs a number of milliseconds")?;
+                let millis: u64 = millis
+                    .parse()
+                    .map_err(|_| format!("'{millis}' is not a number of milliseconds"))?;
+                options.tick_rate = Duration::from_millis(millis);
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option '{flag}'")),
+            dir => options.content_dirs.push(PathBuf::from(dir)),
+        }
+    }
+
+    if options.content_dirs.is_empty() {
+        options.content_dirs.push(PathBuf::from("rooms"));
+    }
+
+    Ok(options)
+}
+
+fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(width, _)| width.0 as usize)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+fn print_map(game: &Game) {
+    for line in render_minimap(&game.minimap(MINIMAP_RADIUS)) {
+        println!("{line}");
+    }
+}
+
+fn print_zone_map(game: &Game) {
+    for line in render_minimap(&game.zone_map(None)) {
+        println!("{line}");
+    }
+}
+
+fn render_outputs(game: &Game, outputs: Vec<Output>, options: &Options) -> Vec<String> {
+    let width = terminal_width();
+    let mut lines = Vec::new();
+    for output in outputs {
+        match output {
+            Output::Message(message) => {
+                lines.push(render_message(&message, width, options.color));
+            }
+            Output::MinimapUpdated if options.auto_map => {
+                lines.push(String::new());
+                lines.extend(render_minimap(&game.minimap(MINIMAP_RADIUS)));
+            }
+            Output::MinimapUpdated => {}
+        }
+    }
+    lines
+}
+
+fn print_outputs(game: &Game, outputs: Vec<Output>, options: &Options) {
+    for line in render_outputs(game, outputs, options) {
+        println!("{line}");
+    }
+}
+
+/// Ticks the world on its own thread until the program exits.
+fn spawn_ticks(
+    game: Arc<Mutex<Game>>,
+    mut printer: impl ExternalPrinter + Send + 'static,
+    options: Options,
+) {
+    thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                eprintln!("Failed to start the world clock: {e}");
+                return;
+            }
+        };
+
+        runtime.block_on(run_ticks(TickControl::new(options.tick_rate), |elapsed| {
+            let Ok(mut game) = game.lock() else {
+                return;
+            };
+            let outputs = game.tick(elapsed);
+            let lines = render_outputs(&game, outputs, &options);
+            drop(game);
+
+            if !lines.is_empty() {
+                let _ = printer.print(lines.join("\n"));
+            }
+        }));
+    });
+}
+
+/// The tick thread only holds the lock briefly, and a panic there leaves the
+/// game itself intact, so a poisoned lock is still usable.
+fn lock_game(game: &Mutex<Game>) -> std::sync::MutexGuard<'_, Game> {
+    game.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut game = match Game::load_from_dirs(&options.content_dirs) {
+        Ok((game, report)) => {
+            if report.warning_count() > 0 {
+                eprintln!("{report}");
+            }
+            game
+        }
+        Err(e) => {
+            eprintln!("Failed to load game: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(saves_dir) = options.saves_dir.clone() {
+        game = game.with_saves_dir(saves_dir);
+    }
+
+    let mut editor = match DefaultEditor::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            eprintln!("Failed to open terminal: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let outputs = game.start_messages();
+    print_outputs(&game, outputs, &options);
+
+    let game = Arc::new(Mutex::new(game));
+    // Without a terminal there is nowhere to print between commands
+    if let Ok(printer) = editor.create_external_printer() {
+        spawn_ticks(Arc::clone(&game), printer, options.clone());
+    }
+
+    loop {
+        // No status prompt exists until character creation is finished
+        let prompt = match lock_game(&game).prompt() {
+            prompt if prompt.is_empty() => ">".to_string(),
+            prompt => prompt,
+        };
+        let line = match editor.readline(&format!("{prompt} ")) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("Error reading input: {e}");
+                return ExitCode::FAILURE;
+            }
+        };
+
+        let command = line.trim();
+        if command.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(command);
+
+        let mut game = lock_game(&game);
+        match command.to_lowercase().as_str() {
+            "quit" | "exit" => break,
+            "map" => print_map(&game),
+            "map zone" => print_zone_map(&game),
+            _ => {
+                let outputs = game.handle_command(command);
+                print_outputs(&game, outputs, &options);
+            }
+        }
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,130 @@
+use muddy_engine::minimap::MinimapNode;
+use muddy_engine::text_utils::{markup_to_ansi, wrap_lines};
+use std::collections::HashMap;
+
+/// Wraps a game message to `width` columns and renders its `**bold**`
+/// markup for the terminal. Bold spans may cross the line breaks introduced
+/// by wrapping.
+pub fn render_message(message: &str, width: usize, color: bool) -> String {
+    markup_to_ansi(&wrap_lines(message, width).join("\n"), color)
+}
+
+/// Draws the minimap as text. Rooms are `[ ]` cells (`[@]` for the player)
+/// laid out on a grid with `-`, `|`, `/` and `\` between connected rooms.
+/// Rooms with stairs show `^` for up, `v` for down or `=` for both, and
+/// rooms that had to be moved off their spot are drawn `{ }`. Unexplored
+/// stubs show `?`.
+pub fn render_minimap(nodes: &[MinimapNode]) -> Vec<String> {
+    if nodes.is_empty() {
+        return Vec::new();
+    }
+
+    let min_x = nodes.iter().map(|n| n.x).min().unwrap_or(0);
+    let max_x = nodes.iter().map(|n| n.x).max().unwrap_or(0);
+    let min_y = nodes.iter().map(|n| n.y).min().unwrap_or(0);
+    let max_y = nodes.iter().map(|n| n.y).max().unwrap_or(0);
+
+    // Each room is three columns wide with one column of gap, and rows are
+    // doubled so that vertical and diagonal links have a line of their own
+    let width = ((max_x - min_x) * 4 + 3) as usize;
+    let height = ((max_y - min_y) * 2 + 1) as usize;
+    let mut grid = vec![vec![' '; width]; height];
+
+    let column = |x: i32| ((x - min_x) * 4) as usize;
+    let row = |y: i32| ((max_y - y) * 2) as usize;
+
+    let positions: HashMap<&str, (i32, i32)> = nodes
+        .iter()
+        .map(|n| (n.room_key.as_str(), (n.x, n.y)))
+        .collect();
+
+    for node in nodes {
+        for target in &node.connections {
+            let Some(&(tx, ty)) = positions.get(target.as_str()) else {
+                continue;
+            };
+            let (dx, dy) = (tx - node.x, ty - node.y);
+            if dx.abs() > 1 || dy.abs() > 1 {
+                continue;
+            }
+
+            let link_row = (row(node.y) as i32 - dy) as usize;
+            let link_col = (column(node.x) as i32 + 1 + dx * 2) as usize;
+            let link = match (dx, dy) {
+                (0, _) => '|',
+                (_, 0) => '-',
+                _ if dx == dy => '/',
+                _ => '\\',
+            };
+
+            let cell = &mut grid[link_row][link_col];
+            *cell = match (*cell, link) {
+                ('/', '\\') | ('\\', '/') => 'X',
+                _ => link,
+            };
+        }
+    }
+
+    for node in nodes {
+        let (r, c) = (row(node.y), column(node.x));
+        let (open, close) = if node.conflict {
+            ('{', '}')
+        } else {
+            ('[', ']')
+        };
+        grid[r][c] = open;
+        grid[r][c + 1] = match (node.is_player, node.up, node.down) {
+            (true, _, _) => '@',
+            _ if node.stub => '?',
+            (_, true, true) => '=',
+            (_, true, false) => '^',
+            (_, false, true) => 'v',
+            _ => ' ',
+        };
+        grid[r][c + 2] = close;
+    }
+
+    grid.into_iter()
+        .map(|line| line.into_iter().collect::<String>().trim_end().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(x: i32, y: i32, key: &str, connections: &[&str]) -> MinimapNode {
+        MinimapNode {
+            x,
+            y,
+            room_key: key.to_string(),
+            room_name: key.to_string(),
+            is_player: x == 0 && y == 0,
+            connections: connections.iter().map(|c| c.to_string()).collect(),
+            up: key == "c",
+            down: false,
+            other_exits: Vec::new(),
+            conflict: key == "b",
+            conflicting_connections: Vec::new(),
+            stub: false,
+        }
+    }
+
+    #[test]
+    fn test_render_message_bold_across_wrap() {
+        let rendered = render_message("a **bold move** b", 12, true);
+        assert_eq!(rendered, "a \x1b[1mbold\nmove\x1b[0m b");
+        assert_eq!(render_message("**Town**", 80, false), "Town");
+    }
+
+    #[test]
+    fn test_render_minimap() {
+        let nodes = vec![
+            node(0, 0, "a", &["b", "c"]),
+            node(1, 0, "b", &["a"]),
+            node(1, 1, "c", &["a"]),
+        ];
+
+        assert_eq!(render_minimap(&nodes), vec!["    [^]", "   /", "[@]-{ }"]);
+    }
+}
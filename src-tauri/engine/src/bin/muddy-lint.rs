@@ -0,0 +1,86 @@
+//! Checks zone content without starting the game window.
+//!
+//! Usage: `muddy-lint [--json] [CONTENT_DIR]...`
+//!
+//! Content directories are layered the same way the game loads them: files in
+//! later directories override files with the same name in earlier ones. A path
+//! to a `zones.json` file is accepted in place of its directory. Exits with 1
+//! when the report contains errors and 2 when the content cannot be read.
+
+use muddy_engine::validation::validate_zones;
+use muddy_engine::zone::{read_content_file, read_zone_source, OBJECTS_FILE, ZONES_FILE};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: muddy-lint [--json] [CONTENT_DIR]...";
+
+fn main() -> ExitCode {
+    let mut json_output = false;
+    let mut content_dirs = Vec::new();
+
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json_output = true,
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return ExitCode::SUCCESS;
+            }
+            flag if flag.starts_with('-') => {
+                eprintln!("Unknown option '{flag}'\n{USAGE}");
+                return ExitCode::from(2);
+            }
+            path => content_dirs.push(content_dir(path)),
+        }
+    }
+
+    if content_dirs.is_empty() {
+        content_dirs.push(PathBuf::from("rooms"));
+    }
+
+    let source = read_zone_source(&content_dirs).and_then(|source| {
+        let objects_json = read_content_file(&content_dirs, OBJECTS_FILE)?;
+        Ok((source, objects_json))
+    });
+    let (source, objects_json) = match source {
+        Ok(source) => source,
+        Err(e) => {
+            if json_output {
+                println!("{}", serde_json::json!({ "fatal": e }));
+            } else {
+                eprintln!("fatal: {e}");
+            }
+            return ExitCode::from(2);
+        }
+    };
+
+    let report = validate_zones(&source.zones_json, &source.zone_file_refs(), &objects_json);
+
+    if json_output {
+        let output = serde_json::json!({
+            "errors": report.error_count(),
+            "warnings": report.warning_count(),
+            "issues": report.issues,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output).unwrap_or_default()
+        );
+    } else {
+        println!("{report}");
+    }
+
+    if report.has_errors() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn content_dir(arg: &str) -> PathBuf {
+    let path = PathBuf::from(arg);
+    if path.file_name().is_some_and(|name| name == ZONES_FILE) {
+        path.parent().map(PathBuf::from).unwrap_or_default()
+    } else {
+        path
+    }
+}
@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const MINUTES_PER_HOUR: u64 = 60;
+pub const HOURS_PER_DAY: u64 = 24;
+pub const DAYS_PER_MONTH: u64 = 30;
+pub const MONTH_NAMES: [&str; 12] = [
+    "the Wolf",
+    "Frost",
+    "the Thaw",
+    "Rain",
+    "Blossoms",
+    "the Sun",
+    "Long Days",
+    "the Harvest",
+    "Falling Leaves",
+    "the Hunt",
+    "Mists",
+    "the Long Night",
+];
+
+const MINUTES_PER_DAY: u64 = MINUTES_PER_HOUR * HOURS_PER_DAY;
+const MINUTES_PER_MONTH: u64 = MINUTES_PER_DAY * DAYS_PER_MONTH;
+const MINUTES_PER_YEAR: u64 = MINUTES_PER_MONTH * MONTH_NAMES.len() as u64;
+
+/// Hours of the day, inclusive of `SUNRISE_HOUR` and exclusive of
+/// `SUNSET_HOUR`, when it counts as daytime.
+const SUNRISE_HOUR: u64 = 6;
+const SUNSET_HOUR: u64 = 20;
+
+/// How fast game time runs, from the optional `clock` entry of `zones.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    /// Real seconds that make up one game hour.
+    pub real_seconds_per_hour: u64,
+    /// Hour of the first day that a freshly loaded world starts at.
+    pub start_hour: u64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            real_seconds_per_hour: 60,
+            start_hour: 8,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeOfDay {
+    Day,
+    Night,
+}
+
+/// The in-game calendar: 24-hour days, 30-day months and 12-month years,
+/// counted in whole game minutes from the first minute of year 1.
+#[derive(Debug, Clone)]
+pub struct GameClock {
+    minutes: u64,
+    real_per_minute: Duration,
+    /// Real time that has passed but does not yet add up to a game minute.
+    carry: Duration,
+}
+
+impl GameClock {
+    pub fn new(config: &ClockConfig) -> Self {
+        let real_per_hour = Duration::from_secs(config.real_seconds_per_hour.max(1));
+        Self {
+            minutes: config.start_hour % HOURS_PER_DAY * MINUTES_PER_HOUR,
+            real_per_minute: real_per_hour / MINUTES_PER_HOUR as u32,
+            carry: Duration::ZERO,
+        }
+    }
+
+    /// Moves game time on by however much `real_elapsed` is worth.
+    pub fn advance(&mut self, real_elapsed: Duration) {
+        self.carry += real_elapsed;
+        let minutes = self.carry.as_nanos() / self.real_per_minute.as_nanos();
+        self.carry -= self.real_per_minute * minutes as u32;
+        self.advance_minutes(minutes as u64);
+    }
+
+    pub fn advance_minutes(&mut self, minutes: u64) {
+        self.minutes += minutes;
+    }
+
+    /// Game minutes since the first minute of year 1.
+    pub fn total_minutes(&self) -> u64 {
+        self.minutes
+    }
+
+    /// Jumps to `minutes` since the first minute of year 1, as when a saved
+    /// game is restored.
+    pub fn set_total_minutes(&mut self, minutes: u64) {
+        self.minutes = minutes;
+        self.carry = Duration::ZERO;
+    }
+
+    pub fn minute(&self) -> u64 {
+        self.minutes % MINUTES_PER_HOUR
+    }
+
+    pub fn hour(&self) -> u64 {
+        self.minutes % MINUTES_PER_DAY / MINUTES_PER_HOUR
+    }
+
+    /// Day of the month, starting at 1.
+    pub fn day(&self) -> u64 {
+        self.minutes % MINUTES_PER_MONTH / MINUTES_PER_DAY + 1
+    }
+
+    pub fn month_name(&self) -> &'static str {
+        MONTH_NAMES[(self.minutes % MINUTES_PER_YEAR / MINUTES_PER_MONTH) as usize]
+    }
+
+    /// Year, starting at 1.
+    pub fn year(&self) -> u64 {
+        self.minutes / MINUTES_PER_YEAR + 1
+    }
+
+    pub fn time_of_day(&self) -> TimeOfDay {
+        if (SUNRISE_HOUR..SUNSET_HOUR).contains(&self.hour()) {
+            TimeOfDay::Day
+        } else {
+            TimeOfDay::Night
+        }
+    }
+
+    /// What the `time` command reports.
+    pub fn describe(&self) -> Vec<String> {
+        let hour = match self.hour() % 12 {
+            0 => 12,
+            hour => hour,
+        };
+        let half = if self.hour() < 12 { "am" } else { "pm" };
+
+        vec![
+            format!(
+                "It is {hour}:{:02}{half}, on the {} day of the Month of {}, year {}.",
+                self.minute(),
+                ordinal(self.day()),
+                self.month_name(),
+                self.year()
+            ),
+            match self.time_of_day() {
+                TimeOfDay::Day => "The sun is up.".to_string(),
+                TimeOfDay::Night => "It is night.".to_string(),
+            },
+        ]
+    }
+}
+
+fn ordinal(number: u64) -> String {
+    let suffix = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{number}{suffix}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_real_time_drives_the_calendar() {
+        let mut clock = GameClock::new(&ClockConfig::default());
+        assert_eq!(clock.time_of_day(), TimeOfDay::Day);
+
+        // One real minute is one game hour; half a second is not yet a minute
+        clock.advance(Duration::from_millis(500));
+        assert_eq!((clock.hour(), clock.minute()), (8, 0));
+        clock.advance(Duration::from_secs(12 * 60) + Duration::from_millis(500));
+        assert_eq!((clock.hour(), clock.minute()), (20, 1));
+        assert_eq!(clock.time_of_day(), TimeOfDay::Night);
+
+        clock.advance_minutes(MINUTES_PER_MONTH + 3 * MINUTES_PER_HOUR);
+        assert_eq!(
+            clock.describe(),
+            [
+                "It is 11:01pm, on the 1st day of the Month of Frost, year 1.",
+                "It is night."
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::equipment::{armor_bonus, effective_attributes, weapon_damage};
+use crate::mobile::MobileDef;
+use crate::object::ObjectMap;
+use crate::player::Player;
+use crate::stats::modifier;
+use crate::text_utils::capitalize;
+use rand::Rng;
+
+const BASE_ARMOR: i32 = 10;
+const UNARMED_DAMAGE: (i32, i32) = (1, 4);
+
+/// Chance that a `flee` attempt finds a way out.
+pub const FLEE_CHANCE: f64 = 0.5;
+
+/// What one side of a fight brings to a single swing.
+#[derive(Debug, Clone, Copy)]
+pub struct Combatant {
+    pub level: u32,
+    pub armor: i32,
+    pub damage: (i32, i32),
+}
+
+impl Combatant {
+    /// Dexterity makes a player harder to hit and strength hits harder, on
+    /// top of whatever armor and weapon they have on.
+    pub fn for_player(player: &Player, objects: &ObjectMap) -> Self {
+        let equipment = &player.equipment;
+        let attributes = effective_attributes(&player.stats.attributes, equipment, objects);
+        let strength = modifier(attributes.strength);
+        let (min, max) = weapon_damage(equipment, objects).unwrap_or(UNARMED_DAMAGE);
+
+        Self {
+            level: player.stats.level,
+            armor: BASE_ARMOR + modifier(attributes.dexterity) + armor_bonus(equipment, objects),
+            damage: (min + strength, max + strength),
+        }
+    }
+
+    pub fn for_mobile(def: &MobileDef) -> Self {
+        Self {
+            level: def.level,
+            armor: def.armor,
+            damage: def.damage,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Swing {
+    Miss,
+    Hit(i32),
+}
+
+/// Rolls a d20 plus the attacker's level against the defender's armor, and
+/// damage within the attacker's range on a hit.
+pub fn swing(rng: &mut impl Rng, attacker: &Combatant, defender: &Combatant) -> Swing {
+    let roll = rng.gen_range(1..=20) + attacker.level as i32;
+    if roll < defender.armor {
+        return Swing::Miss;
+    }
+
+    let (min, max) = attacker.damage;
+    Swing::Hit(rng.gen_range(min..=max.max(min)).max(1))
+}
+
+/// Experience for killing a mobile of `level`.
+pub fn xp_reward(level: u32) -> u32 {
+    20 * level
+}
+
+/// The classic `consider` verdict for a target of `target_level`.
+pub fn consider_message(player_level: u32, target_level: u32, target: &str) -> String {
+    match target_level as i32 - player_level as i32 {
+        ..=-3 => format!("You could kill {target} with a needle."),
+        -2..=-1 => format!("{} is no match for you.", capitalize(target)),
+        0 => format!("The perfect match: you and {target}!"),
+        1..=2 => format!("You would need some luck to beat {target}."),
+        _ => format!("Are you mad!? {} would tear you apart.", capitalize(target)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_swings_repeat_with_the_same_seed() {
+        let attacker = Combatant {
+            level: 3,
+            armor: 10,
+            damage: (2, 6),
+        };
+        let defender = Combatant {
+            level: 1,
+            armor: 12,
+            damage: (1, 2),
+        };
+
+        let fight = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..20)
+                .map(|_| swing(&mut rng, &attacker, &defender))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(fight(7), fight(7));
+        assert!(fight(7).iter().all(|swing| match swing {
+            Swing::Hit(damage) => (2..=6).contains(damage),
+            Swing::Miss => true,
+        }));
+    }
+}
@@ -0,0 +1,795 @@
+use crate::clock::TimeOfDay;
+use crate::object::{describe_room_objects, find_object, ObjectFlag, ObjectMap};
+use crate::player::Player;
+use crate::room::{format_exits, Exit, RoomLocation};
+use crate::shop::coins;
+use crate::text_utils::capitalize;
+use crate::zone::RoomMap;
+use std::collections::HashMap;
+use std::fmt;
+
+/// What a command does, independent of how the player spelled it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verb {
+    /// Leaves by the exit the command is named after.
+    Move,
+    Travel,
+    Look,
+    Get,
+    Drop,
+    Inventory,
+    Examine,
+    Wear,
+    Wield,
+    Remove,
+    Equipment,
+    Open,
+    Close,
+    Lock,
+    Unlock,
+    Search,
+    Kill,
+    Flee,
+    Consider,
+    List,
+    Buy,
+    Sell,
+    Value,
+    Save,
+    Restore,
+    Saves,
+    Help,
+    Score,
+    Time,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Args {
+    None,
+    Optional,
+    /// At least one argument. The word asks for it, as in `Kill whom?`.
+    Required(&'static str),
+}
+
+#[derive(Debug)]
+pub struct CommandSpec {
+    pub verb: Verb,
+    pub name: &'static str,
+    /// Other words that select the command exactly, e.g. `i` and `inv`.
+    pub aliases: &'static [&'static str],
+    /// Shortest abbreviation of `name` that selects the command. Where
+    /// abbreviations overlap, the command listed first in [`COMMANDS`] wins.
+    pub min_prefix: usize,
+    pub args: Args,
+    /// How the arguments are shown in help, e.g. `<item>`.
+    pub usage: &'static str,
+    /// The help line the command is listed under.
+    pub category: &'static str,
+}
+
+/// Command names are unique, so they identify a command.
+impl PartialEq for CommandSpec {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for CommandSpec {}
+
+const fn command(
+    verb: Verb,
+    name: &'static str,
+    aliases: &'static [&'static str],
+    min_prefix: usize,
+    args: Args,
+    usage: &'static str,
+    category: &'static str,
+) -> CommandSpec {
+    CommandSpec {
+        verb,
+        name,
+        aliases,
+        min_prefix,
+        args,
+        usage,
+        category,
+    }
+}
+
+/// Every command, in the order help lists them and abbreviations are tried.
+#[rustfmt::skip]
+pub const COMMANDS: &[CommandSpec] = &[
+    command(Verb::Move, "north", &["n"], 1, Args::None, "", "Movement"),
+    command(Verb::Move, "south", &["s"], 1, Args::None, "", "Movement"),
+    command(Verb::Move, "east", &["e"], 1, Args::None, "", "Movement"),
+    command(Verb::Move, "west", &["w"], 1, Args::None, "", "Movement"),
+    command(Verb::Move, "northeast", &["ne"], 6, Args::None, "", "Movement"),
+    command(Verb::Move, "northwest", &["nw"], 6, Args::None, "", "Movement"),
+    command(Verb::Move, "southeast", &["se"], 6, Args::None, "", "Movement"),
+    command(Verb::Move, "southwest", &["sw"], 6, Args::None, "", "Movement"),
+    command(Verb::Move, "up", &["u"], 1, Args::None, "", "Movement"),
+    command(Verb::Move, "down", &["d"], 1, Args::None, "", "Movement"),
+    command(Verb::Travel, "travel", &["goto"], 2, Args::Required("where"), "<room>", "Movement"),
+    command(Verb::Get, "get", &["take"], 1, Args::Required("what"), "<item>", "Items"),
+    command(Verb::Drop, "drop", &[], 2, Args::Required("what"), "<item>", "Items"),
+    command(Verb::Inventory, "inventory", &["i"], 1, Args::None, "", "Items"),
+    command(Verb::Examine, "examine", &["x"], 2, Args::Required("what"), "<target>", "Items"),
+    command(Verb::Wear, "wear", &[], 3, Args::Required("what"), "<item>", "Equipment"),
+    command(Verb::Wield, "wield", &[], 2, Args::Required("what"), "<weapon>", "Equipment"),
+    command(Verb::Remove, "remove", &[], 3, Args::Required("what"), "<item>", "Equipment"),
+    command(Verb::Equipment, "equipment", &[], 2, Args::None, "", "Equipment"),
+    command(Verb::Open, "open", &[], 1, Args::Required("what"), "<door>", "Doors"),
+    command(Verb::Close, "close", &[], 2, Args::Required("what"), "<door>", "Doors"),
+    command(Verb::Lock, "lock", &[], 3, Args::Required("what"), "<door>", "Doors"),
+    command(Verb::Unlock, "unlock", &[], 2, Args::Required("what"), "<door>", "Doors"),
+    command(Verb::Search, "search", &[], 3, Args::None, "", "Doors"),
+    command(Verb::Kill, "kill", &["k", "attack"], 2, Args::Required("whom"), "<mobile>", "Combat"),
+    command(Verb::Flee, "flee", &[], 1, Args::None, "", "Combat"),
+    command(Verb::Consider, "consider", &[], 2, Args::Required("whom"), "<mobile>", "Combat"),
+    command(Verb::List, "list", &[], 2, Args::None, "", "Shops"),
+    command(Verb::Buy, "buy", &[], 1, Args::Required("what"), "<item>", "Shops"),
+    command(Verb::Sell, "sell", &[], 3, Args::Required("what"), "<item>", "Shops"),
+    command(Verb::Value, "value", &[], 1, Args::Required("what"), "<item>", "Shops"),
+    command(Verb::Save, "save", &[], 2, Args::Optional, "[slot]", "Game"),
+    command(Verb::Restore, "restore", &[], 3, Args::Optional, "[slot]", "Game"),
+    command(Verb::Saves, "saves", &[], 5, Args::None, "", "Game"),
+    command(Verb::Help, "help", &["?"], 1, Args::Optional, "", "Other"),
+    command(Verb::Look, "look", &["l"], 1, Args::Optional, "[target]", "Other"),
+    command(Verb::Score, "score", &[], 2, Args::None, "", "Other"),
+    command(Verb::Time, "time", &[], 2, Args::None, "", "Other"),
+];
+
+/// Why a line of input is not a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+    Empty,
+    Unknown(String),
+    MissingArgument(&'static CommandSpec),
+    UnexpectedArgument(&'static CommandSpec),
+    UnterminatedQuote,
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::Empty => write!(f, "What?"),
+            CommandError::Unknown(word) => write!(
+                f,
+                "Unknown command: '{word}'. Type 'help' for available commands."
+            ),
+            CommandError::MissingArgument(spec) => match spec.args {
+                Args::Required(word) => write!(f, "{} {word}?", capitalize(spec.name)),
+                _ => write!(f, "Usage: {} {}", spec.name, spec.usage),
+            },
+            CommandError::UnexpectedArgument(spec) => {
+                write!(f, "'{}' doesn't take any arguments.", spec.name)
+            }
+            CommandError::UnterminatedQuote => write!(f, "You didn't close your quotes."),
+        }
+    }
+}
+
+/// A line of input resolved against [`COMMANDS`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Command {
+    pub spec: &'static CommandSpec,
+    pub args: Vec<String>,
+}
+
+impl Command {
+    pub fn verb(&self) -> Verb {
+        self.spec.verb
+    }
+
+    /// The arguments as one name, e.g. `rusty sword` from `get rusty sword`.
+    pub fn target(&self) -> String {
+        self.args.join(" ")
+    }
+}
+
+/// The command `word` selects by name, alias or abbreviation, ignoring case.
+pub fn find_command(word: &str) -> Option<&'static CommandSpec> {
+    let word = word.to_lowercase();
+    COMMANDS
+        .iter()
+        .find(|spec| spec.name == word || spec.aliases.contains(&word.as_str()))
+        .or_else(|| {
+            COMMANDS
+                .iter()
+                .find(|spec| word.len() >= spec.min_prefix && spec.name.starts_with(&word))
+        })
+}
+
+/// Splits input into words, keeping double-quoted text together as one.
+pub fn tokenize(input: &str) -> Result<Vec<String>, CommandError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut quoted = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                quoted = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() || quoted {
+                    tokens.push(std::mem::take(&mut current));
+                }
+                quoted = false;
+            }
+            c => current.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(CommandError::UnterminatedQuote);
+    }
+    if !current.is_empty() || quoted {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+/// Parses a line of input into a command and its arguments, checking the
+/// arguments against what the command takes.
+pub fn parse_command(input: &str) -> Result<Command, CommandError> {
+    let mut tokens = tokenize(input)?.into_iter();
+    let word = tokens.next().ok_or(CommandError::Empty)?;
+    let spec = find_command(&word).ok_or(CommandError::Unknown(word))?;
+    let args: Vec<String> = tokens.collect();
+
+    match spec.args {
+        Args::None if !args.is_empty() => Err(CommandError::UnexpectedArgument(spec)),
+        Args::Required(_) if args.is_empty() => Err(CommandError::MissingArgument(spec)),
+        _ => Ok(Command { spec, args }),
+    }
+}
+
+/// The `help` listing, one line per category of [`COMMANDS`].
+pub fn help_text() -> Vec<String> {
+    let mut categories: Vec<(&str, Vec<String>)> = Vec::new();
+    for spec in COMMANDS {
+        let mut entry = spec.name.to_string();
+        if !spec.aliases.is_empty() {
+            entry.push_str(&format!(" ({})", spec.aliases.join(", ")));
+        }
+        if !spec.usage.is_empty() {
+            entry.push_str(&format!(" {}", spec.usage));
+        }
+
+        match categories
+            .iter_mut()
+            .find(|(name, _)| *name == spec.category)
+        {
+            Some((_, entries)) => entries.push(entry),
+            None => categories.push((spec.category, vec![entry])),
+        }
+    }
+
+    let mut lines = vec!["Available commands:".to_string()];
+    lines.extend(
+        categories
+            .into_iter()
+            .map(|(category, entries)| format!("  {category}: {}", entries.join(", "))),
+    );
+    lines.push("  Speedwalk: several moves at once, e.g. 3n2e or n;n;e".to_string());
+    lines
+}
+
+pub fn expand_direction(input: &str) -> &str {
+    match input {
+        "n" => "north",
+        "s" => "south",
+        "e" => "east",
+        "w" => "west",
+        "ne" => "northeast",
+        "nw" => "northwest",
+        "se" => "southeast",
+        "sw" => "southwest",
+        "u" => "up",
+        "d" => "down",
+        other => other,
+    }
+}
+
+/// The most moves one speedwalk may expand to.
+pub const MAX_SPEEDWALK_STEPS: usize = 50;
+
+/// Longest first, so that `ne` is northeast rather than north then east.
+const SPEEDWALK_DIRECTIONS: [&str; 20] = [
+    "northeast",
+    "northwest",
+    "southeast",
+    "southwest",
+    "north",
+    "south",
+    "east",
+    "west",
+    "up",
+    "down",
+    "ne",
+    "nw",
+    "se",
+    "sw",
+    "n",
+    "s",
+    "e",
+    "w",
+    "u",
+    "d",
+];
+
+/// Expands speedwalk notation such as `3n2e`, `n;n;e` or `2north;e` into
+/// the full direction names of each move. Returns `None` unless the whole
+/// input is made of directions with optional counts.
+pub fn parse_speedwalk(input: &str) -> Option<Vec<&'static str>> {
+    let mut directions = Vec::new();
+
+    for part in input.split(|c: char| c == ';' || c.is_whitespace()) {
+        let mut rest = part;
+        while !rest.is_empty() {
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            // Counts past the limit are clamped so that callers still see
+            // the walk is too long without expanding all of it
+            let count = match digits {
+                0 => 1,
+                _ => rest[..digits]
+                    .parse::<usize>()
+                    .unwrap_or(usize::MAX)
+                    .min(MAX_SPEEDWALK_STEPS + 1),
+            };
+            if count == 0 {
+                return None;
+            }
+            rest = &rest[digits..];
+
+            let direction = SPEEDWALK_DIRECTIONS
+                .into_iter()
+                .find(|direction| rest.starts_with(direction))?;
+            rest = &rest[direction.len()..];
+            directions.extend(std::iter::repeat_n(expand_direction(direction), count));
+        }
+    }
+
+    (!directions.is_empty()).then_some(directions)
+}
+
+/// How other occupants see someone arrive after moving in `direction`.
+pub fn arrival_message(name: &str, direction: &str) -> String {
+    let from = match direction {
+        "north" => "the south",
+        "south" => "the north",
+        "east" => "the west",
+        "west" => "the east",
+        "northeast" => "the southwest",
+        "northwest" => "the southeast",
+        "southeast" => "the northwest",
+        "southwest" => "the northeast",
+        "up" => return format!("{name} arrives from below."),
+        "down" => return format!("{name} arrives from above."),
+        _ => return format!("{name} has arrived."),
+    };
+    format!("{name} arrives from {from}.")
+}
+
+pub fn process_move(
+    player: &mut Player,
+    rooms: &RoomMap,
+    objects: &ObjectMap,
+    time: TimeOfDay,
+    command: &str,
+) -> Result<Vec<String>, String> {
+    let direction = expand_direction(command);
+
+    let key = player.current_location.to_key();
+    let (room, zone) = rooms
+        .get(&key)
+        .ok_or_else(|| "Error: Current room not found.".to_string())?;
+
+    let exit = room
+        .exits
+        .get(direction)
+        .filter(|exit| !exit.hidden)
+        .ok_or_else(|| "You can't go that way.".to_string())?;
+
+    if let Some(door) = exit.door.as_ref().filter(|door| door.closed) {
+        return Err(format!("The {} is closed.", door.name));
+    }
+
+    let destination = RoomLocation::parse(&exit.to, zone).map_err(|e| format!("Error: {e}"))?;
+
+    if !rooms.contains_key(&destination.to_key()) {
+        return Err(format!(
+            "Error: Destination room not found ({})",
+            destination.to_key()
+        ));
+    }
+
+    player.move_to(destination);
+    Ok(get_room_display(player, rooms, objects, time))
+}
+
+pub fn get_room_display(
+    player: &Player,
+    rooms: &RoomMap,
+    objects: &ObjectMap,
+    time: TimeOfDay,
+) -> Vec<String> {
+    let key = player.current_location.to_key();
+    let Some((room, _)) = rooms.get(&key) else {
+        return Vec::new();
+    };
+
+    let mut messages = vec![
+        format!("**{}**", room.name),
+        room.description_at(time).to_string(),
+    ];
+
+    let exits = format_exits(&room.exits);
+    if !exits.is_empty() {
+        messages.push(String::new());
+        messages.push(exits);
+        messages.extend(describe_closed_doors(&room.exits));
+    }
+
+    let object_lines = describe_room_objects(&room.objects, objects);
+    if !object_lines.is_empty() {
+        messages.push(String::new());
+        messages.extend(object_lines);
+    }
+
+    messages
+}
+
+fn describe_closed_doors(exits: &HashMap<String, Exit>) -> Vec<String> {
+    let mut lines: Vec<String> = exits
+        .iter()
+        .filter(|(_, exit)| !exit.hidden)
+        .filter_map(|(direction, exit)| {
+            let door = exit.door.as_ref().filter(|door| door.closed)?;
+            Some(format!("The {} to the {direction} is closed.", door.name))
+        })
+        .collect();
+    lines.sort();
+    lines
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoorAction {
+    Open,
+    Close,
+    Lock,
+    Unlock,
+}
+
+impl DoorAction {
+    fn verb(self) -> &'static str {
+        match self {
+            DoorAction::Open => "open",
+            DoorAction::Close => "close",
+            DoorAction::Lock => "lock",
+            DoorAction::Unlock => "unlock",
+        }
+    }
+}
+
+/// Finds the visible door `target` names, either by direction or by the
+/// door's own name, and returns the direction of its exit.
+fn find_door(exits: &HashMap<String, Exit>, target: &str) -> Option<String> {
+    let direction = expand_direction(target);
+    let mut doors: Vec<(&String, &Exit)> = exits
+        .iter()
+        .filter(|(_, exit)| !exit.hidden && exit.door.is_some())
+        .collect();
+    doors.sort_by_key(|(direction, _)| direction.as_str());
+
+    doors
+        .iter()
+        .find(|(d, _)| d.as_str() == direction)
+        .or_else(|| {
+            doors
+                .iter()
+                .find(|(_, exit)| exit.door.as_ref().is_some_and(|door| door.name == target))
+        })
+        .map(|(direction, _)| direction.to_string())
+}
+
+pub fn process_door(
+    player: &Player,
+    rooms: &mut RoomMap,
+    action: DoorAction,
+    target: &str,
+) -> Vec<String> {
+    let key = player.current_location.to_key();
+    let Some((room, zone)) = rooms.get_mut(&key) else {
+        return vec!["Error: Current room not found.".to_string()];
+    };
+
+    let Some(direction) = find_door(&room.exits, target) else {
+        return vec![format!("You see no '{target}' here.")];
+    };
+    let exit = room
+        .exits
+        .get_mut(&direction)
+        .expect("door direction exists");
+    let Some(door) = exit.door.as_mut() else {
+        return vec![format!("You see no '{target}' here.")];
+    };
+
+    let has_key = door.key.is_some_and(|id| player.inventory.contains(&id));
+    let result = match action {
+        DoorAction::Open if !door.closed => Err("is already open"),
+        DoorAction::Open if door.locked => Err("is locked"),
+        DoorAction::Open => {
+            door.closed = false;
+            Ok(())
+        }
+        DoorAction::Close if door.closed => Err("is already closed"),
+        DoorAction::Close => {
+            door.closed = true;
+            Ok(())
+        }
+        DoorAction::Lock if door.locked => Err("is already locked"),
+        DoorAction::Lock if door.key.is_none() => Err("has no lock"),
+        DoorAction::Lock if !door.closed => Err("must be closed first"),
+        DoorAction::Unlock if !door.locked => Err("isn't locked"),
+        DoorAction::Lock | DoorAction::Unlock if !has_key => {
+            return vec!["You don't have the key.".to_string()];
+        }
+        DoorAction::Lock => {
+            door.locked = true;
+            Ok(())
+        }
+        DoorAction::Unlock => {
+            door.locked = false;
+            Ok(())
+        }
+    };
+
+    let name = door.name.clone();
+    if let Err(problem) = result {
+        return vec![format!("The {name} {problem}.")];
+    }
+
+    let (closed, locked) = (door.closed, door.locked);
+    if !exit.one_way {
+        let far_side = RoomLocation::parse(&exit.to, zone).map(|location| location.to_key());
+        if let Ok(far_key) = far_side {
+            mirror_door(rooms, &far_key, &key, closed, locked);
+        }
+    }
+
+    vec![format!("You {} the {name}.", action.verb())]
+}
+
+/// Copies a door's state to the exit on the other side that leads back to
+/// `from_key`, so both rooms agree on whether the door is open.
+pub(crate) fn mirror_door(
+    rooms: &mut RoomMap,
+    far_key: &str,
+    from_key: &str,
+    closed: bool,
+    locked: bool,
+) {
+    let Some((room, zone)) = rooms.get_mut(far_key) else {
+        return;
+    };
+
+    let leads_back = |exit: &Exit| {
+        RoomLocation::parse(&exit.to, zone).is_ok_and(|location| location.to_key() == from_key)
+    };
+    for exit in room.exits.values_mut() {
+        if !leads_back(exit) {
+            continue;
+        }
+        if let Some(door) = exit.door.as_mut() {
+            door.closed = closed;
+            door.locked = locked;
+        }
+    }
+}
+
+/// Reveals the hidden exits of the player's room to everyone until the zone
+/// next resets.
+pub fn process_search(player: &Player, rooms: &mut RoomMap) -> Vec<String> {
+    let key = player.current_location.to_key();
+    let Some((room, _)) = rooms.get_mut(&key) else {
+        return vec!["Error: Current room not found.".to_string()];
+    };
+
+    let mut found: Vec<&String> = room
+        .exits
+        .iter_mut()
+        .filter(|(_, exit)| exit.hidden)
+        .map(|(direction, exit)| {
+            exit.hidden = false;
+            direction
+        })
+        .collect();
+    found.sort();
+
+    if found.is_empty() {
+        return vec!["You search the area but find nothing unusual.".to_string()];
+    }
+    found
+        .into_iter()
+        .map(|direction| format!("You discover a hidden exit to the **{direction}**!"))
+        .collect()
+}
+
+pub fn process_get(
+    player: &mut Player,
+    rooms: &mut RoomMap,
+    objects: &ObjectMap,
+    target: &str,
+) -> Vec<String> {
+    let key = player.current_location.to_key();
+    let Some((room, _)) = rooms.get_mut(&key) else {
+        return vec!["Error: Current room not found.".to_string()];
+    };
+
+    let Some(index) = find_object(target, &room.objects, objects, true) else {
+        return vec![format!("You don't see '{target}' here.")];
+    };
+
+    let object = &objects[&room.objects[index]];
+    if object.has_flag(ObjectFlag::NoTake) {
+        return vec![format!("You can't take {}.", object.short_description)];
+    }
+
+    player.inventory.push(room.objects.remove(index));
+    vec![format!("You get {}.", object.short_description)]
+}
+
+pub fn process_drop(
+    player: &mut Player,
+    rooms: &mut RoomMap,
+    objects: &ObjectMap,
+    target: &str,
+) -> Vec<String> {
+    let Some(index) = find_object(target, &player.inventory, objects, false) else {
+        return vec![format!("You aren't carrying '{target}'.")];
+    };
+
+    let key = player.current_location.to_key();
+    let Some((room, _)) = rooms.get_mut(&key) else {
+        return vec!["Error: Current room not found.".to_string()];
+    };
+
+    let object_id = player.inventory.remove(index);
+    room.objects.push(object_id);
+    vec![format!(
+        "You drop {}.",
+        objects[&object_id].short_description
+    )]
+}
+
+pub fn get_inventory_display(player: &Player, objects: &ObjectMap) -> Vec<String> {
+    let mut messages = vec!["You are carrying:".to_string()];
+
+    let carried: Vec<String> = player
+        .inventory
+        .iter()
+        .filter_map(|id| objects.get(id))
+        .map(|obj| format!("  {}", obj.short_description))
+        .collect();
+
+    if carried.is_empty() {
+        messages.push("  Nothing.".to_string());
+    } else {
+        messages.extend(carried);
+    }
+    messages.push(format!("Your purse holds {}.", coins(player.gold)));
+
+    messages
+}
+
+pub fn process_examine(
+    player: &Player,
+    rooms: &RoomMap,
+    objects: &ObjectMap,
+    target: &str,
+) -> Vec<String> {
+    // Carried items take precedence over items lying in the room
+    let object_id = find_object(target, &player.inventory, objects, false)
+        .map(|index| player.inventory[index])
+        .or_else(|| {
+            let (room, _) = rooms.get(&player.current_location.to_key())?;
+            find_object(target, &room.objects, objects, true).map(|index| room.objects[index])
+        });
+
+    let Some(object) = object_id.and_then(|id| objects.get(&id)) else {
+        return vec![format!("You don't see '{target}' here.")];
+    };
+
+    let description = object.examine_description.clone().unwrap_or_else(|| {
+        format!(
+            "You see nothing special about {}.",
+            object.short_description
+        )
+    });
+
+    vec![
+        format!("**{}**", capitalize(&object.short_description)),
+        description,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_abbreviation_resolves_to_its_command() {
+        for spec in COMMANDS {
+            for len in spec.min_prefix..=spec.name.len() {
+                assert_eq!(
+                    find_command(&spec.name[..len]),
+                    Some(spec),
+                    "{}",
+                    &spec.name[..len]
+                );
+            }
+            for alias in spec.aliases {
+                assert_eq!(find_command(alias), Some(spec));
+            }
+        }
+        assert_eq!(
+            find_command("INV").map(|spec| spec.verb),
+            Some(Verb::Inventory)
+        );
+        assert_eq!(
+            find_command("exa").map(|spec| spec.verb),
+            Some(Verb::Examine)
+        );
+        assert_eq!(find_command("lo").map(|spec| spec.verb), Some(Verb::Look));
+        assert_eq!(find_command("xyzzy"), None);
+    }
+
+    #[test]
+    fn test_parse_speedwalk() {
+        assert_eq!(
+            parse_speedwalk("3n2e").unwrap(),
+            ["north", "north", "north", "east", "east"]
+        );
+        assert_eq!(
+            parse_speedwalk("n;n;e").unwrap(),
+            ["north", "north", "east"]
+        );
+        assert_eq!(
+            parse_speedwalk("2ne; up").unwrap(),
+            ["northeast", "northeast", "up"]
+        );
+        assert_eq!(
+            parse_speedwalk("99999999999999999999n").unwrap().len(),
+            MAX_SPEEDWALK_STEPS + 1
+        );
+        assert_eq!(parse_speedwalk("0n"), None);
+        assert_eq!(parse_speedwalk("3x"), None);
+        assert_eq!(parse_speedwalk(";"), None);
+    }
+
+    #[test]
+    fn test_parse_command_arguments_and_errors() {
+        let command = parse_command(r#"Get  "rusty sword"  2"#).unwrap();
+        assert_eq!(command.verb(), Verb::Get);
+        assert_eq!(command.args, ["rusty sword", "2"]);
+
+        assert_eq!(parse_command("   "), Err(CommandError::Empty));
+        assert_eq!(
+            parse_command("say \"hi"),
+            Err(CommandError::UnterminatedQuote)
+        );
+        assert_eq!(parse_command("kill").unwrap_err().to_string(), "Kill whom?");
+        assert_eq!(
+            parse_command("inv sword").unwrap_err().to_string(),
+            "'inventory' doesn't take any arguments."
+        );
+        assert_eq!(
+            parse_command("dance").unwrap_err(),
+            CommandError::Unknown("dance".to_string())
+        );
+    }
+}
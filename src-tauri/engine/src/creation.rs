@@ -0,0 +1,471 @@
+use crate::player::validate_name;
+use crate::stats::{Attribute, Attributes};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Point-buy starts every attribute here, before race and class modifiers.
+const POINT_BUY_BASE: u32 = 8;
+/// The highest score point-buy can raise an attribute to.
+const POINT_BUY_MAX: u32 = 15;
+/// Points to spend, one per attribute point.
+const POINT_BUY_POINTS: u32 = 27;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Race {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Added to the rolled attributes.
+    #[serde(default)]
+    pub modifiers: HashMap<Attribute, i32>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Class {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    #[serde(default)]
+    pub modifiers: HashMap<Attribute, i32>,
+    /// Added to the starting maximums.
+    #[serde(default)]
+    pub bonus_hp: i32,
+    #[serde(default)]
+    pub bonus_mana: i32,
+    #[serde(default)]
+    pub bonus_stamina: i32,
+}
+
+/// The races and classes a new character can pick from. With none defined,
+/// creation skips straight from the name to the attribute roll.
+#[derive(Debug, Clone, Default)]
+pub struct CharacterData {
+    pub races: Vec<Race>,
+    pub classes: Vec<Class>,
+}
+
+impl CharacterData {
+    pub fn load(races_json: &str, classes_json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            races: serde_json::from_str(races_json)?,
+            classes: serde_json::from_str(classes_json)?,
+        })
+    }
+
+    pub fn race(&self, id: &str) -> Option<&Race> {
+        self.races.iter().find(|race| race.id == id)
+    }
+
+    pub fn class(&self, id: &str) -> Option<&Class> {
+        self.classes.iter().find(|class| class.id == id)
+    }
+
+    /// A character's name with their race and class, e.g. `Alice the Dwarf
+    /// Warrior`. Unknown ids are left out.
+    pub fn title(&self, name: &str, race: Option<&str>, class: Option<&str>) -> String {
+        let kind: Vec<&str> = [
+            race.and_then(|id| self.race(id))
+                .map(|race| race.name.as_str()),
+            class
+                .and_then(|id| self.class(id))
+                .map(|class| class.name.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        if kind.is_empty() {
+            name.to_string()
+        } else {
+            format!("{name} the {}", kind.join(" "))
+        }
+    }
+}
+
+/// A finished character, ready to be put into the world.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Character {
+    pub name: String,
+    pub race: Option<String>,
+    pub class: Option<String>,
+    pub attributes: Attributes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Name,
+    Race,
+    Class,
+    Attributes,
+    PointBuy,
+    Confirm,
+}
+
+#[derive(Debug)]
+pub enum CreationResult {
+    /// Lines to show, ending with the question for the next input.
+    Continue(Vec<String>),
+    Done(Character),
+}
+
+/// Walks a new player through name, race, class, attribute roll or
+/// point-buy and confirmation, one line of input at a time.
+#[derive(Debug, Clone)]
+pub struct CharacterCreation {
+    step: Step,
+    name: String,
+    race: Option<usize>,
+    class: Option<usize>,
+    attributes: Attributes,
+    /// Scores bought so far, before modifiers.
+    bought: Attributes,
+}
+
+impl Default for CharacterCreation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CharacterCreation {
+    pub fn new() -> Self {
+        Self {
+            step: Step::Name,
+            name: String::new(),
+            race: None,
+            class: None,
+            attributes: Attributes::default(),
+            bought: Attributes::default(),
+        }
+    }
+
+    /// The question for the current step.
+    pub fn prompt(&self, data: &CharacterData) -> Vec<String> {
+        match self.step {
+            Step::Name => vec!["By what name do you wish to be known?".to_string()],
+            Step::Race => choice_prompt(
+                "Choose your race:",
+                data.races.iter().map(|r| (&r.name, &r.description)),
+            ),
+            Step::Class => choice_prompt(
+                "Choose your class:",
+                data.classes.iter().map(|c| (&c.name, &c.description)),
+            ),
+            Step::Attributes => vec![
+                "Your attributes:".to_string(),
+                format!("  {}", self.attributes.summary()),
+                "Type 'roll' to roll again, 'keep' to keep them or 'buy' to spend points instead."
+                    .to_string(),
+            ],
+            Step::PointBuy => vec![
+                format!("You have {} points to spend:", self.points_left()),
+                format!("  {}", self.bought.summary()),
+                format!(
+                    "Type an attribute and a score from {POINT_BUY_BASE} to {POINT_BUY_MAX}, \
+                     e.g. 'str 14', then 'done'. Type 'roll' to roll instead."
+                ),
+            ],
+            Step::Confirm => vec![format!(
+                "You will be {}. Is this correct? (yes/no)",
+                data.title(
+                    &self.name,
+                    self.race.map(|index| data.races[index].id.as_str()),
+                    self.class.map(|index| data.classes[index].id.as_str()),
+                )
+            )],
+        }
+    }
+
+    /// Handles one line of input. `name_available` says whether nobody else
+    /// is already using a name.
+    pub fn handle_input(
+        &mut self,
+        input: &str,
+        data: &CharacterData,
+        rng: &mut impl Rng,
+        name_available: impl Fn(&str) -> bool,
+    ) -> CreationResult {
+        let input = input.trim();
+        let mut lines = Vec::new();
+
+        match self.step {
+            Step::Name => match validate_name(input) {
+                Ok(name) if !name_available(&name) => {
+                    lines.push(format!("Someone called {name} is already playing."));
+                }
+                Ok(name) => {
+                    self.name = name;
+                    self.advance(data, rng);
+                }
+                Err(e) => lines.push(e),
+            },
+            Step::Race => match find_choice(input, data.races.iter().map(|r| &r.name)) {
+                Some(index) => {
+                    self.race = Some(index);
+                    self.advance(data, rng);
+                }
+                None => lines.push(format!("'{input}' is not one of the races.")),
+            },
+            Step::Class => match find_choice(input, data.classes.iter().map(|c| &c.name)) {
+                Some(index) => {
+                    self.class = Some(index);
+                    self.advance(data, rng);
+                }
+                None => lines.push(format!("'{input}' is not one of the classes.")),
+            },
+            Step::Attributes => match input.to_lowercase().as_str() {
+                "roll" | "r" => self.roll(data, rng),
+                "keep" | "k" => self.step = Step::Confirm,
+                "buy" | "b" => {
+                    self.bought = point_buy_start();
+                    self.step = Step::PointBuy;
+                }
+                _ => lines.push("Please type 'roll', 'keep' or 'buy'.".to_string()),
+            },
+            Step::PointBuy => match input.to_lowercase().as_str() {
+                "done" | "d" => {
+                    let bought = self.bought.clone();
+                    self.apply_modifiers(data, |attribute| bought.get(attribute));
+                    self.step = Step::Confirm;
+                }
+                "roll" | "r" => {
+                    self.roll(data, rng);
+                    self.step = Step::Attributes;
+                }
+                other => {
+                    if let Err(e) = self.buy(other) {
+                        lines.push(e);
+                    }
+                }
+            },
+            Step::Confirm => match input.to_lowercase().as_str() {
+                "yes" | "y" => return CreationResult::Done(self.finish(data)),
+                "no" | "n" => {
+                    *self = Self::new();
+                    lines.push("Let's start over.".to_string());
+                }
+                _ => lines.push("Please answer yes or no.".to_string()),
+            },
+        }
+
+        lines.extend(self.prompt(data));
+        CreationResult::Continue(lines)
+    }
+
+    fn advance(&mut self, data: &CharacterData, rng: &mut impl Rng) {
+        self.step = match self.step {
+            Step::Name if !data.races.is_empty() => Step::Race,
+            Step::Name | Step::Race if !data.classes.is_empty() => Step::Class,
+            _ => {
+                self.roll(data, rng);
+                Step::Attributes
+            }
+        };
+    }
+
+    /// Rolls 4d6 and drops the lowest die for every attribute, then applies
+    /// the chosen race and class modifiers.
+    fn roll(&mut self, data: &CharacterData, rng: &mut impl Rng) {
+        self.apply_modifiers(data, |_| {
+            let mut dice: Vec<u32> = (0..4).map(|_| rng.gen_range(1..=6)).collect();
+            dice.sort_unstable();
+            dice[1..].iter().sum()
+        });
+    }
+
+    /// Sets every attribute to its `base` score plus the chosen race and
+    /// class modifiers, never below 3.
+    fn apply_modifiers(&mut self, data: &CharacterData, mut base: impl FnMut(Attribute) -> u32) {
+        let race = self.race.map(|index| &data.races[index].modifiers);
+        let class = self.class.map(|index| &data.classes[index].modifiers);
+
+        for attribute in Attribute::ALL {
+            let bonus: i32 = [race, class]
+                .into_iter()
+                .flatten()
+                .filter_map(|modifiers| modifiers.get(&attribute))
+                .sum();
+            *self.attributes.get_mut(attribute) = (base(attribute) as i32 + bonus).max(3) as u32;
+        }
+    }
+
+    fn points_left(&self) -> u32 {
+        let spent: u32 = Attribute::ALL
+            .iter()
+            .map(|&attribute| self.bought.get(attribute) - POINT_BUY_BASE)
+            .sum();
+        POINT_BUY_POINTS - spent
+    }
+
+    /// Handles `<attribute> <score>`, e.g. `str 14`, during point-buy.
+    fn buy(&mut self, input: &str) -> Result<(), String> {
+        let usage = || "Type an attribute and a score, e.g. 'str 14'.".to_string();
+        let (name, score) = input.split_once(' ').ok_or_else(usage)?;
+        let attribute = Attribute::ALL
+            .into_iter()
+            .find(|attribute| {
+                name.len() >= 3 && format!("{attribute:?}").to_lowercase().starts_with(name)
+            })
+            .ok_or_else(|| format!("'{name}' is not an attribute."))?;
+        let score: u32 = score.trim().parse().map_err(|_| usage())?;
+
+        if !(POINT_BUY_BASE..=POINT_BUY_MAX).contains(&score) {
+            return Err(format!(
+                "Scores must be between {POINT_BUY_BASE} and {POINT_BUY_MAX}."
+            ));
+        }
+        let current = self.bought.get(attribute);
+        if score > current && score - current > self.points_left() {
+            return Err(format!("You only have {} points left.", self.points_left()));
+        }
+        *self.bought.get_mut(attribute) = score;
+        Ok(())
+    }
+
+    fn finish(&self, data: &CharacterData) -> Character {
+        Character {
+            name: self.name.clone(),
+            race: self.race.map(|index| data.races[index].id.clone()),
+            class: self.class.map(|index| data.classes[index].id.clone()),
+            attributes: self.attributes.clone(),
+        }
+    }
+}
+
+fn point_buy_start() -> Attributes {
+    let mut attributes = Attributes::default();
+    for attribute in Attribute::ALL {
+        *attributes.get_mut(attribute) = POINT_BUY_BASE;
+    }
+    attributes
+}
+
+fn choice_prompt<'a>(
+    title: &str,
+    choices: impl Iterator<Item = (&'a String, &'a String)>,
+) -> Vec<String> {
+    let mut lines = vec![title.to_string()];
+    lines.extend(choices.enumerate().map(|(index, (name, description))| {
+        format!("  {}. **{name}** - {description}", index + 1)
+    }));
+    lines.push("Type the name or number of your choice.".to_string());
+    lines
+}
+
+/// Accepts a 1-based number or the start of a name.
+fn find_choice<'a>(input: &str, names: impl Iterator<Item = &'a String>) -> Option<usize> {
+    let names: Vec<&String> = names.collect();
+    if let Ok(number) = input.parse::<usize>() {
+        return (1..=names.len()).contains(&number).then(|| number - 1);
+    }
+
+    let input = input.to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+    names
+        .iter()
+        .position(|name| name.to_lowercase().starts_with(&input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const RACES: &str = r#"[
+        {"id": "human", "name": "Human", "description": "Adaptable."},
+        {"id": "dwarf", "name": "Dwarf", "description": "Stout.", "modifiers": {"constitution": 2}}
+    ]"#;
+    const CLASSES: &str = r#"[
+        {"id": "warrior", "name": "Warrior", "description": "Fights.", "bonus_hp": 10}
+    ]"#;
+
+    #[test]
+    fn test_walks_through_every_step() {
+        let data = CharacterData::load(RACES, CLASSES).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut creation = CharacterCreation::new();
+        let mut input = |creation: &mut CharacterCreation, line: &str| {
+            creation.handle_input(line, &data, &mut rng, |name| name != "Taken")
+        };
+
+        let CreationResult::Continue(lines) = input(&mut creation, "taken") else {
+            panic!("name should be rejected");
+        };
+        assert_eq!(lines[0], "Someone called Taken is already playing.");
+
+        input(&mut creation, "alice");
+        input(&mut creation, "dw");
+        input(&mut creation, "1");
+        input(&mut creation, "roll");
+        let CreationResult::Continue(lines) = input(&mut creation, "keep") else {
+            panic!("confirmation should be asked for");
+        };
+        assert_eq!(
+            lines,
+            ["You will be Alice the Dwarf Warrior. Is this correct? (yes/no)"]
+        );
+
+        let CreationResult::Done(character) = input(&mut creation, "yes") else {
+            panic!("creation should be finished");
+        };
+        assert_eq!(character.name, "Alice");
+        assert_eq!(character.race.as_deref(), Some("dwarf"));
+        assert_eq!(character.class.as_deref(), Some("warrior"));
+        assert!(character.attributes.constitution >= 5);
+    }
+
+    #[test]
+    fn test_point_buy() {
+        let data = CharacterData::load(RACES, CLASSES).unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut creation = CharacterCreation::new();
+        let mut input = |creation: &mut CharacterCreation, line: &str| match creation.handle_input(
+            line,
+            &data,
+            &mut rng,
+            |_| true,
+        ) {
+            CreationResult::Continue(lines) => lines,
+            CreationResult::Done(_) => panic!("creation finished early"),
+        };
+
+        for line in ["bob", "dwarf", "warrior"] {
+            input(&mut creation, line);
+        }
+        let lines = input(&mut creation, "buy");
+        assert_eq!(lines[0], "You have 27 points to spend:");
+
+        assert_eq!(
+            input(&mut creation, "str 16")[0],
+            "Scores must be between 8 and 15."
+        );
+        assert_eq!(
+            input(&mut creation, "luck 10")[0],
+            "'luck' is not an attribute."
+        );
+        input(&mut creation, "strength 15");
+        input(&mut creation, "con 15");
+        input(&mut creation, "dex 15");
+        assert_eq!(
+            input(&mut creation, "int 15")[0],
+            "You only have 6 points left."
+        );
+        let lines = input(&mut creation, "con 13");
+        assert_eq!(lines[0], "You have 8 points to spend:");
+
+        input(&mut creation, "done");
+        let CreationResult::Done(character) =
+            creation.handle_input("yes", &data, &mut rng, |_| true)
+        else {
+            panic!("creation should be finished");
+        };
+        assert_eq!(character.attributes.strength, 15);
+        // The dwarf's +2 lands on top of the bought score.
+        assert_eq!(character.attributes.constitution, 15);
+        assert_eq!(character.attributes.wisdom, 8);
+    }
+}
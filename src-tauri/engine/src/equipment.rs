@@ -0,0 +1,292 @@
+use crate::object::{find_object, ObjectDef, ObjectFlag, ObjectMap};
+use crate::player::Player;
+use crate::stats::{Attribute, Attributes};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Where on the body an object can be worn. Objects say which slot they
+/// fit with a `wear_*` or `wield` flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WearSlot {
+    Head,
+    Neck,
+    Body,
+    Arms,
+    Hands,
+    Legs,
+    Feet,
+    Shield,
+    Wield,
+}
+
+impl WearSlot {
+    pub const ALL: [WearSlot; 9] = [
+        WearSlot::Head,
+        WearSlot::Neck,
+        WearSlot::Body,
+        WearSlot::Arms,
+        WearSlot::Hands,
+        WearSlot::Legs,
+        WearSlot::Feet,
+        WearSlot::Shield,
+        WearSlot::Wield,
+    ];
+
+    /// The object flag that marks an object as fitting this slot.
+    pub fn flag(self) -> ObjectFlag {
+        match self {
+            WearSlot::Head => ObjectFlag::WearHead,
+            WearSlot::Neck => ObjectFlag::WearNeck,
+            WearSlot::Body => ObjectFlag::WearBody,
+            WearSlot::Arms => ObjectFlag::WearArms,
+            WearSlot::Hands => ObjectFlag::WearHands,
+            WearSlot::Legs => ObjectFlag::WearLegs,
+            WearSlot::Feet => ObjectFlag::WearFeet,
+            WearSlot::Shield => ObjectFlag::WearShield,
+            WearSlot::Wield => ObjectFlag::Wield,
+        }
+    }
+
+    /// Where the object goes, as in `You wear a cap on your head.`
+    fn placement(self) -> &'static str {
+        match self {
+            WearSlot::Head => "on your head",
+            WearSlot::Neck => "around your neck",
+            WearSlot::Body => "on your body",
+            WearSlot::Arms => "on your arms",
+            WearSlot::Hands => "on your hands",
+            WearSlot::Legs => "on your legs",
+            WearSlot::Feet => "on your feet",
+            WearSlot::Shield => "as a shield",
+            WearSlot::Wield => "as your weapon",
+        }
+    }
+
+    /// The column label of the `equipment` listing.
+    fn label(self) -> &'static str {
+        match self {
+            WearSlot::Head => "<worn on head>",
+            WearSlot::Neck => "<worn around neck>",
+            WearSlot::Body => "<worn on body>",
+            WearSlot::Arms => "<worn on arms>",
+            WearSlot::Hands => "<worn on hands>",
+            WearSlot::Legs => "<worn on legs>",
+            WearSlot::Feet => "<worn on feet>",
+            WearSlot::Shield => "<worn as shield>",
+            WearSlot::Wield => "<wielded>",
+        }
+    }
+}
+
+/// Object ids a player has on, by slot.
+pub type Equipment = BTreeMap<WearSlot, u32>;
+
+impl ObjectDef {
+    /// The first slot the object's flags say it fits, if any.
+    pub fn wear_slot(&self) -> Option<WearSlot> {
+        WearSlot::ALL
+            .into_iter()
+            .find(|slot| self.has_flag(slot.flag()))
+    }
+
+    /// The object's bonuses, e.g. `2-7 damage, +1 armor, +1 Str`.
+    pub fn bonus_summary(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some((min, max)) = self.damage {
+            parts.push(format!("{min}-{max} damage"));
+        }
+        if self.armor != 0 {
+            parts.push(format!("{:+} armor", self.armor));
+        }
+        for attribute in Attribute::ALL {
+            if let Some(&bonus) = self.modifiers.get(&attribute).filter(|&&b| b != 0) {
+                parts.push(format!("{bonus:+} {}", attribute.abbreviation()));
+            }
+        }
+        parts.join(", ")
+    }
+}
+
+fn equipped<'a>(
+    equipment: &'a Equipment,
+    objects: &'a ObjectMap,
+) -> impl Iterator<Item = &'a ObjectDef> {
+    equipment.values().filter_map(|id| objects.get(id))
+}
+
+/// Attributes with the modifiers of everything worn added, never below 1.
+pub fn effective_attributes(
+    attributes: &Attributes,
+    equipment: &Equipment,
+    objects: &ObjectMap,
+) -> Attributes {
+    let mut effective = attributes.clone();
+    for attribute in Attribute::ALL {
+        let bonus: i32 = equipped(equipment, objects)
+            .filter_map(|obj| obj.modifiers.get(&attribute))
+            .sum();
+        let score = effective.get_mut(attribute);
+        *score = (*score as i32 + bonus).max(1) as u32;
+    }
+    effective
+}
+
+pub fn armor_bonus(equipment: &Equipment, objects: &ObjectMap) -> i32 {
+    equipped(equipment, objects).map(|obj| obj.armor).sum()
+}
+
+/// Damage range of the wielded weapon, if there is one.
+pub fn weapon_damage(equipment: &Equipment, objects: &ObjectMap) -> Option<(i32, i32)> {
+    equipment
+        .get(&WearSlot::Wield)
+        .and_then(|id| objects.get(id))
+        .and_then(|obj| obj.damage)
+}
+
+/// Puts on an object from the player's inventory. `wield` only accepts
+/// weapons; `wear` takes anything that fits a slot.
+pub fn process_wear(
+    player: &mut Player,
+    objects: &ObjectMap,
+    wield: bool,
+    target: &str,
+) -> Vec<String> {
+    let verb = if wield { "wield" } else { "wear" };
+    let Some(index) = find_object(target, &player.inventory, objects, false) else {
+        return vec![format!("You aren't carrying '{target}'.")];
+    };
+    let object = &objects[&player.inventory[index]];
+    let slot = match object.wear_slot() {
+        Some(slot) if !wield || slot == WearSlot::Wield => slot,
+        _ => return vec![format!("You can't {verb} {}.", object.short_description)],
+    };
+
+    if let Some(current) = player.equipment.get(&slot).and_then(|id| objects.get(id)) {
+        return vec![format!(
+            "You are already using {} {}.",
+            current.short_description,
+            slot.placement()
+        )];
+    }
+
+    player
+        .equipment
+        .insert(slot, player.inventory.remove(index));
+    let mut line = match slot {
+        WearSlot::Wield => format!("You wield {}.", object.short_description),
+        _ => format!(
+            "You wear {} {}.",
+            object.short_description,
+            slot.placement()
+        ),
+    };
+    let bonuses = object.bonus_summary();
+    if !bonuses.is_empty() {
+        line.push_str(&format!(" ({bonuses})"));
+    }
+    vec![line]
+}
+
+/// Takes off a worn object and puts it back in the player's inventory.
+pub fn process_remove(player: &mut Player, objects: &ObjectMap, target: &str) -> Vec<String> {
+    let (slots, ids): (Vec<WearSlot>, Vec<u32>) = player.equipment.iter().unzip();
+    let Some(index) = find_object(target, &ids, objects, false) else {
+        return vec![format!("You aren't using '{target}'.")];
+    };
+
+    player.equipment.remove(&slots[index]);
+    player.inventory.push(ids[index]);
+    vec![format!(
+        "You stop using {}.",
+        objects[&ids[index]].short_description
+    )]
+}
+
+pub fn get_equipment_display(player: &Player, objects: &ObjectMap) -> Vec<String> {
+    let mut lines = vec!["You are using:".to_string()];
+
+    let worn: Vec<String> = player
+        .equipment
+        .iter()
+        .filter_map(|(slot, id)| Some((slot, objects.get(id)?)))
+        .map(|(slot, obj)| {
+            let bonuses = obj.bonus_summary();
+            let mut line = format!("  {:<20}{}", slot.label(), obj.short_description);
+            if !bonuses.is_empty() {
+                line.push_str(&format!(" ({bonuses})"));
+            }
+            line
+        })
+        .collect();
+
+    if worn.is_empty() {
+        lines.push("  Nothing.".to_string());
+    } else {
+        lines.extend(worn);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::load_objects;
+
+    const OBJECTS: &str = r#"[
+        {"id": 1, "name": "sword", "keywords": ["sword"], "short_description": "a sword",
+         "long_description": "A sword lies here.", "flags": ["wield"], "damage": [2, 7],
+         "modifiers": {"strength": 1}},
+        {"id": 2, "name": "cap", "keywords": ["cap"], "short_description": "a cap",
+         "long_description": "A cap lies here.", "flags": ["wear_head"], "armor": 1},
+        {"id": 3, "name": "pebble", "keywords": ["pebble"], "short_description": "a pebble",
+         "long_description": "A pebble lies here."}
+    ]"#;
+
+    #[test]
+    fn test_wear_wield_and_remove() {
+        let objects = load_objects(OBJECTS).unwrap();
+        let mut player = Player::new("Alice".to_string(), "town".to_string(), 0);
+        player.inventory = vec![1, 2, 3];
+
+        assert_eq!(
+            process_wear(&mut player, &objects, true, "cap"),
+            ["You can't wield a cap."]
+        );
+        assert_eq!(
+            process_wear(&mut player, &objects, false, "pebble"),
+            ["You can't wear a pebble."]
+        );
+        assert_eq!(
+            process_wear(&mut player, &objects, true, "sword"),
+            ["You wield a sword. (2-7 damage, +1 Str)"]
+        );
+        assert_eq!(
+            process_wear(&mut player, &objects, false, "cap"),
+            ["You wear a cap on your head. (+1 armor)"]
+        );
+        assert_eq!(player.inventory, vec![3]);
+        assert_eq!(armor_bonus(&player.equipment, &objects), 1);
+        assert_eq!(
+            effective_attributes(&player.stats.attributes, &player.equipment, &objects).strength,
+            11
+        );
+
+        assert_eq!(
+            get_equipment_display(&player, &objects),
+            [
+                "You are using:",
+                "  <worn on head>      a cap (+1 armor)",
+                "  <wielded>           a sword (2-7 damage, +1 Str)",
+            ]
+        );
+
+        assert_eq!(
+            process_remove(&mut player, &objects, "sword"),
+            ["You stop using a sword."]
+        );
+        assert_eq!(weapon_damage(&player.equipment, &objects), None);
+        assert_eq!(player.inventory, vec![3, 1]);
+    }
+}
@@ -0,0 +1,411 @@
+use crate::command_parser::{parse_command, Verb};
+use crate::creation::{CharacterCreation, CreationResult};
+use crate::minimap::MinimapNode;
+use crate::pathfinding::RouteStep;
+use crate::player::{Player, PlayerId};
+use crate::save::{list_slots, slot_name, slot_path, SaveGame, SaveSlotInfo, DEFAULT_SLOT};
+use crate::validation::ValidationReport;
+use crate::world::{messages, welcome_lines, Output, World};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// A single-player game: one world, one player, and optional save slots.
+///
+/// The game starts with character creation. Until a character is finished
+/// (or a save is restored) there is no player and every other command goes
+/// to the creation questions.
+pub struct Game {
+    world: World,
+    player_id: Option<PlayerId>,
+    creation: CharacterCreation,
+    saves_dir: Option<PathBuf>,
+}
+
+impl Game {
+    pub fn new(world: World) -> Self {
+        Self {
+            world,
+            player_id: None,
+            creation: CharacterCreation::new(),
+            saves_dir: None,
+        }
+    }
+
+    pub fn load_from_zones(
+        zones_json: &str,
+        zone_files: &[(&str, &str)],
+        objects_json: &str,
+    ) -> Result<Self, String> {
+        World::load_from_zones(zones_json, zone_files, objects_json).map(Self::new)
+    }
+
+    /// Loads the game from `content_dirs`, with the content's warnings; see
+    /// [`World::load_from_dirs`].
+    pub fn load_from_dirs(content_dirs: &[PathBuf]) -> Result<(Self, ValidationReport), String> {
+        let (world, report) = World::load_from_dirs(content_dirs)?;
+        Ok((Self::new(world), report))
+    }
+
+    /// Enables the save commands, storing slots as files in `saves_dir`.
+    pub fn with_saves_dir(mut self, saves_dir: PathBuf) -> Self {
+        self.saves_dir = Some(saves_dir);
+        self
+    }
+
+    pub fn start_messages(&self) -> Vec<Output> {
+        match self.player_id {
+            Some(id) => self.world.start_messages(id),
+            None => {
+                let mut lines = welcome_lines();
+                lines.extend(self.world.creation_prompt(&self.creation));
+                messages(lines)
+            }
+        }
+    }
+
+    /// The status prompt, or an empty string while the character is still
+    /// being created.
+    pub fn prompt(&self) -> String {
+        self.player_id
+            .map(|id| self.world.prompt(id))
+            .unwrap_or_default()
+    }
+
+    pub fn minimap(&self, max_distance: i32) -> Vec<MinimapNode> {
+        self.player_id
+            .map(|id| self.world.minimap(id, max_distance))
+            .unwrap_or_default()
+    }
+
+    pub fn minimap_level(&self, max_distance: i32, level: i32) -> Vec<MinimapNode> {
+        self.player_id
+            .map(|id| self.world.minimap_level(id, max_distance, level))
+            .unwrap_or_default()
+    }
+
+    /// The explored rooms of `zone`, or of the player's zone if `None`.
+    pub fn zone_map(&self, zone: Option<&str>) -> Vec<MinimapNode> {
+        self.player_id
+            .map(|id| self.world.zone_map(id, zone))
+            .unwrap_or_default()
+    }
+
+    /// The route to the room `target` names, for showing on the minimap.
+    pub fn route(&self, target: &str) -> Option<Vec<RouteStep>> {
+        self.world.route(self.player_id?, target)
+    }
+
+    pub fn get_current_room_display(&self) -> Vec<String> {
+        self.player_id
+            .map(|id| self.world.get_room_display(id))
+            .unwrap_or_default()
+    }
+
+    pub fn handle_command(&mut self, command: &str) -> Vec<Output> {
+        // During creation the only slot command is `restore <slot>`, since
+        // names are single words and anything else may be the player's name
+        let parsed = parse_command(command).ok().filter(|parsed| {
+            self.player_id.is_some()
+                || (parsed.verb() == Verb::Restore && !parsed.target().is_empty())
+        });
+        let verb = parsed.as_ref().map(|parsed| parsed.verb());
+        let slot = match parsed.map(|parsed| parsed.target().to_string()) {
+            Some(slot) if !slot.is_empty() => slot,
+            _ => DEFAULT_SLOT.to_string(),
+        };
+
+        // Save slots belong to the single-player game, everything else to the world
+        match verb {
+            Some(Verb::Save) => messages(vec![match self.save_to_slot(&slot) {
+                Ok(saved) => format!("Game saved to slot '{saved}'."),
+                Err(e) => format!("Save failed: {e}"),
+            }]),
+            Some(Verb::Restore) => match self.restore_from_slot(&slot) {
+                Ok(restored) => {
+                    let mut lines = vec![
+                        format!("Game restored from slot '{restored}'."),
+                        String::new(),
+                    ];
+                    lines.extend(self.get_current_room_display());
+                    let mut output = messages(lines);
+                    output.push(Output::MinimapUpdated);
+                    output
+                }
+                Err(e) => messages(vec![format!("Restore failed: {e}")]),
+            },
+            Some(Verb::Saves) => {
+                let slots = self.list_saves();
+                if slots.is_empty() {
+                    return messages(vec!["There are no saved games.".to_string()]);
+                }
+                let mut lines = vec!["Saved games:".to_string()];
+                lines.extend(slots.iter().map(|info| {
+                    format!("  {} - {} ({})", info.slot, info.location, info.saved_at)
+                }));
+                messages(lines)
+            }
+            _ => match self.player_id {
+                Some(id) => self.world.handle_command(id, command),
+                None => self.create_character(command),
+            },
+        }
+    }
+
+    /// Runs one world tick and returns what the player saw happen.
+    pub fn tick(&mut self, elapsed: Duration) -> Vec<Output> {
+        self.world.tick(elapsed);
+        self.world
+            .drain_outbox()
+            .into_iter()
+            .filter(|(id, _)| Some(*id) == self.player_id)
+            .map(|(_, output)| output)
+            .collect()
+    }
+
+    fn create_character(&mut self, input: &str) -> Vec<Output> {
+        match self.world.creation_input(&mut self.creation, input) {
+            CreationResult::Continue(lines) => messages(lines),
+            CreationResult::Done(character) => {
+                let id = self.world.add_character(character);
+                self.player_id = Some(id);
+                self.creation = CharacterCreation::new();
+
+                let mut output = messages(self.world.get_room_display(id));
+                output.push(Output::MinimapUpdated);
+                output
+            }
+        }
+    }
+
+    fn saves_dir(&self) -> Result<&Path, String> {
+        self.saves_dir
+            .as_deref()
+            .ok_or_else(|| "Saving is not available.".to_string())
+    }
+
+    /// Whether character creation is done, so there is something to save.
+    pub fn has_character(&self) -> bool {
+        self.player_id.is_some()
+    }
+
+    fn player(&self) -> Result<&Player, String> {
+        self.player_id
+            .and_then(|id| self.world.player(id))
+            .ok_or_else(|| "You have not created a character yet.".to_string())
+    }
+
+    /// Saves to `slot`, or the existing slot it matches ignoring case, and
+    /// returns the name of the slot saved to.
+    pub fn save_to_slot(&self, slot: &str) -> Result<String, String> {
+        let path = slot_path(self.saves_dir()?, slot)?;
+        let mut save = SaveGame::capture(self.player()?, &self.world.rooms);
+        save.world = Some(self.world.world_state());
+        save.save_to_path(&path)?;
+        Ok(slot_name(&path))
+    }
+
+    /// Restores from `slot`, matched ignoring case, and returns the name of
+    /// the slot restored from.
+    pub fn restore_from_slot(&mut self, slot: &str) -> Result<String, String> {
+        let path = slot_path(self.saves_dir()?, slot)?;
+        if !path.exists() {
+            return Err(format!("No saved game in slot '{slot}'."));
+        }
+        let mut save = SaveGame::load_from_path(&path)?;
+        let world_state = save.world.take();
+
+        // Restoring before creation is finished skips it with the saved character
+        let id = match self.player_id {
+            Some(id) => id,
+            None => self.world.add_player(&save.player.name),
+        };
+        let player = self
+            .world
+            .players
+            .get_mut(&id)
+            .ok_or_else(|| "Player not found.".to_string())?;
+        if let Err(e) = save.apply(player, &mut self.world.rooms) {
+            if self.player_id.is_none() {
+                self.world.remove_player(id);
+            }
+            return Err(e);
+        }
+        if let Some(state) = world_state {
+            self.world.restore_world_state(state);
+        }
+
+        self.player_id = Some(id);
+        self.creation = CharacterCreation::new();
+        Ok(slot_name(&path))
+    }
+
+    pub fn list_saves(&self) -> Vec<SaveSlotInfo> {
+        self.saves_dir().map(list_slots).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game in Millhaven with character creation already done.
+    fn millhaven() -> Game {
+        let mut game = Game::load_from_zones(
+            include_str!("../../rooms/zones.json"),
+            &[
+                ("millhaven.json", include_str!("../../rooms/millhaven.json")),
+                (
+                    "millhaven_mobiles.json",
+                    include_str!("../../rooms/millhaven_mobiles.json"),
+                ),
+            ],
+            include_str!("../../rooms/objects.json"),
+        )
+        .unwrap();
+        for input in ["Tester", "keep", "yes"] {
+            game.handle_command(input);
+        }
+        game
+    }
+
+    fn text(output: &[Output]) -> Vec<&str> {
+        output
+            .iter()
+            .filter_map(|o| match o {
+                Output::Message(line) => Some(line.as_str()),
+                Output::MinimapUpdated => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_move_and_carry_item_between_rooms() {
+        let mut game = millhaven();
+
+        assert_eq!(
+            text(&game.handle_command("get coin")),
+            ["You get a tarnished copper coin."]
+        );
+
+        let output = game.handle_command("n");
+        assert_eq!(text(&output)[0], "**North Road**");
+        assert_eq!(output.last(), Some(&Output::MinimapUpdated));
+
+        assert_eq!(
+            text(&game.handle_command("drop coin")),
+            ["You drop a tarnished copper coin."]
+        );
+        assert!(game.handle_command("look").contains(&Output::Message(
+            "A tarnished copper coin glints between the cobblestones.".to_string()
+        )));
+        assert_eq!(
+            game.handle_command("look coin"),
+            game.handle_command("examine coin")
+        );
+        assert_eq!(
+            text(&game.handle_command("look coin"))[0],
+            "**A tarnished copper coin**"
+        );
+    }
+
+    #[test]
+    fn test_starts_with_character_creation() {
+        let mut game = Game::load_from_zones(
+            include_str!("../../rooms/zones.json"),
+            &[
+                ("millhaven.json", include_str!("../../rooms/millhaven.json")),
+                (
+                    "millhaven_mobiles.json",
+                    include_str!("../../rooms/millhaven_mobiles.json"),
+                ),
+            ],
+            include_str!("../../rooms/objects.json"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            text(&game.start_messages()).last(),
+            Some(&"By what name do you wish to be known?")
+        );
+        assert_eq!(game.prompt(), "");
+
+        // Names that are also slot commands are still names
+        game.handle_command("saves");
+        game.handle_command("keep");
+        assert_eq!(
+            text(&game.handle_command("restore")),
+            [
+                "Please answer yes or no.",
+                "You will be Saves. Is this correct? (yes/no)"
+            ]
+        );
+        game.handle_command("no");
+        game.handle_command("tester");
+        game.handle_command("keep");
+        let output = game.handle_command("yes");
+        assert_eq!(text(&output)[0], "**Town Square**");
+        assert!(game.prompt().starts_with('<'));
+    }
+
+    #[test]
+    fn test_save_slots_keep_their_case() {
+        let dir = std::env::temp_dir().join(format!("muddy-game-test-{}", std::process::id()));
+        let mut game = millhaven().with_saves_dir(dir.clone());
+
+        assert_eq!(
+            text(&game.handle_command("save Hero")),
+            ["Game saved to slot 'Hero'."]
+        );
+        assert_eq!(
+            text(&game.handle_command("save HERO")),
+            ["Game saved to slot 'Hero'."]
+        );
+        let slots: Vec<String> = game
+            .list_saves()
+            .into_iter()
+            .map(|info| info.slot)
+            .collect();
+        assert_eq!(slots, ["Hero"]);
+        assert_eq!(
+            text(&game.handle_command("restore HERO"))[0],
+            "Game restored from slot 'Hero'."
+        );
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_restore_puts_mobiles_and_time_back() {
+        let dir = std::env::temp_dir().join(format!("muddy-world-test-{}", std::process::id()));
+        let mut game = millhaven().with_saves_dir(dir.clone());
+        let here = game.player().unwrap().location().clone();
+        let first = *game.world.mobiles.keys().min().unwrap();
+        let mobile = game.world.mobiles.get_mut(&first).unwrap();
+        mobile.hp = 1;
+        mobile.location = here;
+        game.save_to_slot("world").unwrap();
+        let saved = game.world.world_state();
+
+        game.world.mobiles.clear();
+        game.tick(Duration::from_secs(3600));
+        game.restore_from_slot("world").unwrap();
+
+        let restored = game.world.world_state();
+        assert_eq!(restored.minutes, saved.minutes);
+        assert_eq!(restored.reset_due, saved.reset_due);
+        assert_eq!(restored.mobiles.len(), saved.mobiles.len());
+        assert_eq!(restored.mobiles[0].hp, 1);
+        assert_eq!(restored.mobiles[0].location.to_key(), "millhaven:0");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_save_commands_need_a_saves_dir() {
+        let mut game = millhaven();
+        assert_eq!(
+            text(&game.handle_command("save")),
+            ["Save failed: Saving is not available."]
+        );
+    }
+}
@@ -0,0 +1,24 @@
+pub mod clock;
+pub mod combat;
+pub mod command_parser;
+pub mod creation;
+pub mod equipment;
+mod game;
+pub mod minimap;
+pub mod mobile;
+pub mod object;
+pub mod pathfinding;
+pub mod player;
+pub mod reset;
+pub mod room;
+pub mod save;
+pub mod shop;
+pub mod stats;
+pub mod text_utils;
+pub mod ticker;
+pub mod validation;
+mod world;
+pub mod zone;
+
+pub use game::Game;
+pub use world::{Output, World};
@@ -0,0 +1,569 @@
+use crate::room::{Room, RoomLocation};
+use crate::zone::RoomMap;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MinimapNode {
+    pub x: i32,
+    pub y: i32,
+    pub room_key: String,
+    pub room_name: String,
+    pub is_player: bool,
+    pub connections: Vec<String>,
+    /// Stairs the flat map can't draw as connections.
+    pub up: bool,
+    pub down: bool,
+    /// Other visible exits that aren't compass directions, such as `in`.
+    pub other_exits: Vec<String>,
+    /// The exits around this room don't fit a flat grid: its own spot was
+    /// taken, or a connection leads somewhere other than where its
+    /// direction points.
+    pub conflict: bool,
+    /// The connections that don't match their exit's direction.
+    pub conflicting_connections: Vec<String>,
+    /// An unexplored room seen only as where an explored room's exit leads.
+    /// Its name and exits are left blank.
+    pub stub: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Coord {
+    x: i32,
+    y: i32,
+}
+
+impl Coord {
+    fn apply_direction(&self, direction: &str) -> Option<Self> {
+        match direction {
+            "north" => Some(Coord {
+                x: self.x,
+                y: self.y + 1,
+            }),
+            "south" => Some(Coord {
+                x: self.x,
+                y: self.y - 1,
+            }),
+            "east" => Some(Coord {
+                x: self.x + 1,
+                y: self.y,
+            }),
+            "west" => Some(Coord {
+                x: self.x - 1,
+                y: self.y,
+            }),
+            "northeast" => Some(Coord {
+                x: self.x + 1,
+                y: self.y + 1,
+            }),
+            "northwest" => Some(Coord {
+                x: self.x - 1,
+                y: self.y + 1,
+            }),
+            "southeast" => Some(Coord {
+                x: self.x + 1,
+                y: self.y - 1,
+            }),
+            "southwest" => Some(Coord {
+                x: self.x - 1,
+                y: self.y - 1,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// The free coordinate closest to `wanted`, searching rings of growing
+/// size around it in a fixed order.
+fn nearest_free(wanted: Coord, occupied: &HashMap<Coord, String>) -> Coord {
+    for radius in 1.. {
+        for dy in (-radius..=radius).rev() {
+            for dx in -radius..=radius {
+                let coord = Coord {
+                    x: wanted.x + dx,
+                    y: wanted.y + dy,
+                };
+                if (dx.abs() == radius || dy.abs() == radius) && !occupied.contains_key(&coord) {
+                    return coord;
+                }
+            }
+        }
+    }
+    unreachable!("there is always a free coordinate")
+}
+
+const CARDINAL_DIRECTIONS: [&str; 8] = [
+    "north",
+    "south",
+    "east",
+    "west",
+    "northeast",
+    "northwest",
+    "southeast",
+    "southwest",
+];
+
+pub fn generate_minimap(
+    player_location: &RoomLocation,
+    rooms: &RoomMap,
+    max_distance: i32,
+) -> Vec<MinimapNode> {
+    let player_key = player_location.to_key();
+    minimap_around(&player_key, &player_key, rooms, max_distance)
+}
+
+/// The minimap of the level `level` storeys above the player's (below, if
+/// negative), centred on the nearest room there that `up` and `down` exits
+/// lead to. Empty when no such room can be reached.
+pub fn generate_level_minimap(
+    player_location: &RoomLocation,
+    rooms: &RoomMap,
+    max_distance: i32,
+    level: i32,
+) -> Vec<MinimapNode> {
+    let player_key = player_location.to_key();
+    let mut visited = HashMap::from([(player_key.clone(), 0)]);
+    let mut queue = VecDeque::from([(player_key.clone(), 0)]);
+
+    while let Some((room_key, z)) = queue.pop_front() {
+        if z == level {
+            return minimap_around(&room_key, &player_key, rooms, max_distance);
+        }
+        let Some((room, zone)) = rooms.get(&room_key) else {
+            continue;
+        };
+
+        for direction in CARDINAL_DIRECTIONS.iter().chain(&["up", "down"]) {
+            let Some(next_key) = exit_target(room, zone, direction, rooms) else {
+                continue;
+            };
+            let next_z = match *direction {
+                "up" => z + 1,
+                "down" => z - 1,
+                _ => z,
+            };
+            if !visited.contains_key(&next_key) {
+                visited.insert(next_key.clone(), next_z);
+                queue.push_back((next_key, next_z));
+            }
+        }
+    }
+
+    Vec::new()
+}
+
+/// Key of the room a visible `direction` exit of `room` leads to, if it
+/// exists.
+fn exit_target(room: &Room, zone: &str, direction: &str, rooms: &RoomMap) -> Option<String> {
+    let exit = room.exits.get(direction).filter(|exit| !exit.hidden)?;
+    let next_key = RoomLocation::parse(&exit.to, zone).ok()?.to_key();
+    rooms.contains_key(&next_key).then_some(next_key)
+}
+
+/// How a room reached while laying out a map is drawn.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Reach {
+    /// Left off the map.
+    Skip,
+    /// Drawn without its name or exits, and not followed further.
+    Stub,
+    /// Drawn in full, with its exits followed.
+    Room,
+}
+
+/// Rooms placed on one level so far. Exits that loop back in ways a grid
+/// can't show would put two rooms on one spot; the later room then moves to
+/// the nearest free spot and both it and its stretched connection are
+/// marked as conflicts.
+#[derive(Default)]
+struct Layout {
+    nodes: Vec<MinimapNode>,
+    placed: HashMap<String, Coord>,
+    occupied: HashMap<Coord, String>,
+    conflicts: HashSet<String>,
+    conflict_edges: HashSet<(String, String)>,
+}
+
+impl Layout {
+    /// Puts `room_key` at `wanted`, or the nearest free spot to it.
+    fn place(&mut self, room_key: &str, wanted: Coord) -> Coord {
+        let coord = if self.occupied.contains_key(&wanted) {
+            nearest_free(wanted, &self.occupied)
+        } else {
+            wanted
+        };
+        self.placed.insert(room_key.to_string(), coord);
+        self.occupied.insert(coord, room_key.to_string());
+        coord
+    }
+
+    /// Lays out the rooms reachable from `origin_key`, which goes at
+    /// `origin`, following compass exits into the rooms `reach` allows.
+    fn add(
+        &mut self,
+        origin_key: &str,
+        origin: Coord,
+        player_key: &str,
+        rooms: &RoomMap,
+        reach: impl Fn(&str, Coord) -> Reach,
+    ) {
+        let origin = self.place(origin_key, origin);
+        let mut queue = VecDeque::from([(origin_key.to_string(), origin, Reach::Room)]);
+
+        while let Some((room_key, coord, kind)) = queue.pop_front() {
+            // Add current room to minimap
+            let Some((room, zone)) = rooms.get(&room_key) else {
+                continue;
+            };
+
+            if kind == Reach::Stub {
+                self.nodes.push(MinimapNode {
+                    x: coord.x,
+                    y: coord.y,
+                    is_player: room_key == player_key,
+                    room_key,
+                    room_name: String::new(),
+                    connections: Vec::new(),
+                    up: false,
+                    down: false,
+                    other_exits: Vec::new(),
+                    conflict: false,
+                    conflicting_connections: Vec::new(),
+                    stub: true,
+                });
+                continue;
+            }
+
+            let mut connections = Vec::new();
+
+            for direction in &CARDINAL_DIRECTIONS {
+                let Some(next_key) = exit_target(room, zone, direction, rooms) else {
+                    continue;
+                };
+
+                let next_coord = match coord.apply_direction(direction) {
+                    Some(c) => c,
+                    None => continue,
+                };
+
+                let next_reach = reach(&next_key, next_coord);
+                if next_reach == Reach::Skip {
+                    continue;
+                }
+                // Add to connections regardless of whether we've visited
+                connections.push(next_key.clone());
+
+                let placed = match self.placed.get(&next_key) {
+                    Some(&placed) => placed,
+                    None => {
+                        let placed = self.place(&next_key, next_coord);
+                        queue.push_back((next_key.clone(), placed, next_reach));
+                        placed
+                    }
+                };
+
+                if placed != next_coord {
+                    self.conflicts.insert(room_key.clone());
+                    self.conflicts.insert(next_key.clone());
+                    self.conflict_edges
+                        .insert((room_key.clone(), next_key.clone()));
+                    self.conflict_edges.insert((next_key, room_key.clone()));
+                }
+            }
+
+            let mut other_exits: Vec<String> = room
+                .exits
+                .iter()
+                .filter(|(direction, exit)| {
+                    !exit.hidden
+                        && !CARDINAL_DIRECTIONS.contains(&direction.as_str())
+                        && !matches!(direction.as_str(), "up" | "down")
+                })
+                .map(|(direction, _)| direction.clone())
+                .collect();
+            other_exits.sort();
+
+            self.nodes.push(MinimapNode {
+                x: coord.x,
+                y: coord.y,
+                room_key: room_key.clone(),
+                room_name: room.name.clone(),
+                is_player: room_key == player_key,
+                connections,
+                up: exit_target(room, zone, "up", rooms).is_some(),
+                down: exit_target(room, zone, "down", rooms).is_some(),
+                other_exits,
+                conflict: false,
+                conflicting_connections: Vec::new(),
+                stub: false,
+            });
+        }
+    }
+
+    fn into_nodes(self) -> Vec<MinimapNode> {
+        let mut nodes = self.nodes;
+        // Conflicts can turn up after a room's node is made
+        for node in &mut nodes {
+            node.conflict = self.conflicts.contains(&node.room_key);
+            node.conflicting_connections = node
+                .connections
+                .iter()
+                .filter(|to| {
+                    self.conflict_edges
+                        .contains(&(node.room_key.clone(), to.to_string()))
+                })
+                .cloned()
+                .collect();
+        }
+        nodes
+    }
+}
+
+/// Lays out the rooms around `origin_key` on one level, with `player_key`
+/// marked if it is among them.
+fn minimap_around(
+    origin_key: &str,
+    player_key: &str,
+    rooms: &RoomMap,
+    max_distance: i32,
+) -> Vec<MinimapNode> {
+    let mut layout = Layout::default();
+    layout.add(
+        origin_key,
+        Coord { x: 0, y: 0 },
+        player_key,
+        rooms,
+        |_, coord| {
+            // Only rooms within the max_distance boundary
+            if coord.x.abs() <= max_distance && coord.y.abs() <= max_distance {
+                Reach::Room
+            } else {
+                Reach::Skip
+            }
+        },
+    );
+    layout.into_nodes()
+}
+
+/// Every room of `zone` in `explored`, laid out around the player's room if
+/// it is there, with unexplored rooms their exits lead to as stubs. Parts of
+/// the zone that explored rooms don't join up are drawn side by side. Rooms
+/// in other zones are left off.
+pub fn generate_zone_map(
+    player_location: &RoomLocation,
+    explored: &BTreeSet<String>,
+    rooms: &RoomMap,
+    zone: &str,
+) -> Vec<MinimapNode> {
+    let player_key = player_location.to_key();
+    let in_zone = |key: &str| rooms.get(key).is_some_and(|(_, z)| z == zone);
+
+    let mut origins: Vec<&String> = explored.iter().filter(|key| in_zone(key)).collect();
+    // The player's part of the zone goes in the middle
+    if let Some(index) = origins.iter().position(|key| **key == player_key) {
+        let player_origin = origins.remove(index);
+        origins.insert(0, player_origin);
+    }
+
+    let mut layout = Layout::default();
+    for origin_key in origins {
+        if layout.placed.contains_key(origin_key) {
+            continue;
+        }
+        let origin = match layout.nodes.iter().map(|node| node.x).max() {
+            Some(max_x) => Coord { x: max_x + 2, y: 0 },
+            None => Coord { x: 0, y: 0 },
+        };
+        layout.add(origin_key, origin, &player_key, rooms, |key, _| {
+            if !in_zone(key) {
+                Reach::Skip
+            } else if explored.contains(key) {
+                Reach::Room
+            } else {
+                Reach::Stub
+            }
+        });
+    }
+    layout.into_nodes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::{Exit, Room};
+    use std::collections::{HashMap, HashSet};
+
+    fn create_test_room(id: u32, name: &str, exits: Vec<(&str, &str)>) -> Room {
+        Room {
+            id,
+            name: name.to_string(),
+            description: "Test room".to_string(),
+            night_description: None,
+            exits: exits
+                .iter()
+                .map(|(k, v)| (k.to_string(), Exit::new(*v)))
+                .collect(),
+            objects: vec![],
+        }
+    }
+
+    #[test]
+    fn test_only_cardinal_directions() {
+        let mut rooms = HashMap::new();
+
+        let start = create_test_room(0, "Start", vec![("north", "1"), ("up", "2")]);
+        let r1 = create_test_room(1, "North", vec![("south", "0")]);
+        let r2 = create_test_room(2, "Up", vec![("down", "0")]);
+
+        rooms.insert("test:0".to_string(), (start, "test".to_string()));
+        rooms.insert("test:1".to_string(), (r1, "test".to_string()));
+        rooms.insert("test:2".to_string(), (r2, "test".to_string()));
+
+        let player_location = RoomLocation {
+            zone: "test".to_string(),
+            room_id: 0,
+        };
+
+        let minimap = generate_minimap(&player_location, &rooms, 2);
+        let room_keys: HashSet<String> = minimap.iter().map(|n| n.room_key.clone()).collect();
+
+        assert!(room_keys.contains("test:0"));
+        assert!(room_keys.contains("test:1"));
+        assert!(
+            !room_keys.contains("test:2"),
+            "Should not include 'up' exit"
+        );
+    }
+
+    /// A room with stairs up and a named `in` exit, and the rooms they lead to.
+    fn stair_rooms() -> RoomMap {
+        let mut rooms = HashMap::new();
+        for room in [
+            create_test_room(0, "Start", vec![("north", "1"), ("up", "2"), ("in", "1")]),
+            create_test_room(1, "North", vec![("south", "0")]),
+            create_test_room(2, "Up", vec![("down", "0")]),
+        ] {
+            rooms.insert(format!("test:{}", room.id), (room, "test".to_string()));
+        }
+        rooms
+    }
+
+    #[test]
+    fn test_stairs_and_named_exits_flagged() {
+        let player_location = RoomLocation {
+            zone: "test".to_string(),
+            room_id: 0,
+        };
+
+        let minimap = generate_minimap(&player_location, &stair_rooms(), 2);
+        let start = minimap.iter().find(|n| n.room_key == "test:0").unwrap();
+        assert!(start.up && !start.down);
+        assert_eq!(start.other_exits, ["in"]);
+        let north = minimap.iter().find(|n| n.room_key == "test:1").unwrap();
+        assert!(!north.up && north.other_exits.is_empty());
+    }
+
+    #[test]
+    fn test_level_minimap() {
+        let rooms = stair_rooms();
+        let player_location = RoomLocation {
+            zone: "test".to_string(),
+            room_id: 0,
+        };
+
+        // The floor above is its own map, centred where the stairs arrive
+        let upstairs = generate_level_minimap(&player_location, &rooms, 2, 1);
+        assert_eq!(upstairs.len(), 1);
+        assert_eq!(upstairs[0].room_key, "test:2");
+        assert!(upstairs[0].down && !upstairs[0].is_player);
+        assert_eq!(
+            generate_level_minimap(&player_location, &rooms, 2, 0)[0].room_key,
+            "test:0"
+        );
+        assert!(generate_level_minimap(&player_location, &rooms, 2, -1).is_empty());
+    }
+
+    #[test]
+    fn test_overlapping_rooms_are_moved_and_marked() {
+        let mut rooms = HashMap::new();
+        // Going round a square leads somewhere other than where it began
+        for room in [
+            create_test_room(0, "Start", vec![("east", "1")]),
+            create_test_room(1, "East", vec![("north", "2")]),
+            create_test_room(2, "Corner", vec![("west", "3")]),
+            create_test_room(3, "North", vec![("south", "4")]),
+            create_test_room(4, "Elsewhere", vec![]),
+        ] {
+            rooms.insert(format!("test:{}", room.id), (room, "test".to_string()));
+        }
+        let player_location = RoomLocation {
+            zone: "test".to_string(),
+            room_id: 0,
+        };
+
+        let minimap = generate_minimap(&player_location, &rooms, 2);
+        let node = |key: &str| minimap.iter().find(|n| n.room_key == key).unwrap();
+        let coords: HashSet<(i32, i32)> = minimap.iter().map(|n| (n.x, n.y)).collect();
+        assert_eq!(coords.len(), minimap.len(), "rooms must not overlap");
+
+        // Elsewhere would land on the start room, so it moves aside
+        assert_ne!((node("test:4").x, node("test:4").y), (0, 0));
+        assert!(node("test:4").conflict && node("test:3").conflict);
+        assert_eq!(node("test:3").conflicting_connections, ["test:4"]);
+        assert!(!node("test:0").conflict && !node("test:2").conflict);
+        assert!(node("test:2").conflicting_connections.is_empty());
+    }
+
+    #[test]
+    fn test_zone_map_shows_explored_rooms_and_stubs() {
+        let mut rooms = HashMap::new();
+        for (zone, room) in [
+            (
+                "town",
+                create_test_room(0, "Square", vec![("east", "1"), ("north", "woods:0")]),
+            ),
+            (
+                "town",
+                create_test_room(1, "Street", vec![("east", "2"), ("west", "0")]),
+            ),
+            (
+                "town",
+                create_test_room(2, "Gate", vec![("east", "3"), ("west", "1")]),
+            ),
+            ("town", create_test_room(3, "Road", vec![("west", "2")])),
+            ("town", create_test_room(4, "Cellar", vec![])),
+            (
+                "woods",
+                create_test_room(0, "Clearing", vec![("south", "town:0")]),
+            ),
+        ] {
+            rooms.insert(format!("{zone}:{}", room.id), (room, zone.to_string()));
+        }
+        let player_location = RoomLocation {
+            zone: "town".to_string(),
+            room_id: 0,
+        };
+        let explored: BTreeSet<String> = ["town:0", "town:1", "town:4", "woods:0"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let map = generate_zone_map(&player_location, &explored, &rooms, "town");
+        let node = |key: &str| map.iter().find(|n| n.room_key == key);
+
+        assert_eq!(map.len(), 4);
+        assert!(node("town:0").unwrap().is_player);
+        assert_eq!(node("town:0").unwrap().connections, ["town:1"]);
+        // The gate is known only from the street's exit; the road not at all
+        let gate = node("town:2").unwrap();
+        assert!(gate.stub && gate.room_name.is_empty() && gate.connections.is_empty());
+        assert!(node("town:3").is_none());
+        // The cellar isn't joined to the rest, so it sits off to the side
+        let cellar = node("town:4").unwrap();
+        assert!(!cellar.stub && (cellar.x, cellar.y) == (4, 0));
+
+        let woods = generate_zone_map(&player_location, &explored, &rooms, "woods");
+        assert_eq!(woods.len(), 1);
+        assert!(!woods[0].is_player);
+    }
+}
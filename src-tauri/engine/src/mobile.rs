@@ -0,0 +1,187 @@
+use crate::object::parse_ordinal;
+use crate::room::RoomLocation;
+use crate::shop::Shop;
+use crate::text_utils::keywords_match;
+use crate::zone::{zone_file, ZoneInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+pub type MobileId = u64;
+
+/// Chance per tick that a wandering mobile walks out of its room.
+pub const WANDER_CHANCE: f64 = 0.2;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct LootEntry {
+    pub object: u32,
+    /// Chance from 0.0 to 1.0 that the object drops when the mobile dies.
+    #[serde(default = "always")]
+    pub chance: f64,
+}
+
+fn always() -> f64 {
+    1.0
+}
+
+/// A kind of NPC, loaded from a zone's mobiles file. One instance spawns in
+/// each of `rooms` whenever its zone resets and it is missing.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MobileDef {
+    pub id: u32,
+    pub name: String,
+    pub keywords: Vec<String>,
+    pub short_description: String,
+    /// Line shown for the mobile when it is in a room.
+    pub long_description: String,
+    /// Shown when a player examines the mobile.
+    pub description: String,
+    pub level: u32,
+    pub max_hp: i32,
+    /// Damage range of one hit, inclusive.
+    pub damage: (i32, i32),
+    #[serde(default = "default_armor")]
+    pub armor: i32,
+    #[serde(default = "default_attack_verb")]
+    pub attack_verb: String,
+    /// Aggressive mobiles attack players who walk into their room.
+    #[serde(default)]
+    pub aggressive: bool,
+    /// Wandering mobiles roam between open exits of their own zone.
+    #[serde(default)]
+    pub wanders: bool,
+    #[serde(default)]
+    pub loot: Vec<LootEntry>,
+    /// Makes the mobile a shopkeeper that players can trade with.
+    #[serde(default)]
+    pub shop: Option<Shop>,
+    /// Room ids in the mobile's own zone to spawn it in.
+    #[serde(default)]
+    pub rooms: Vec<u32>,
+}
+
+fn default_armor() -> i32 {
+    10
+}
+
+fn default_attack_verb() -> String {
+    "hits".to_string()
+}
+
+impl MobileDef {
+    /// Whether `name` refers to this mobile; see [`keywords_match`].
+    pub fn matches(&self, name: &str) -> bool {
+        keywords_match(&self.keywords, name)
+    }
+}
+
+pub type MobileDefMap = HashMap<u32, MobileDef>;
+
+pub fn load_mobiles(mobiles_json: &str) -> Result<Vec<MobileDef>, serde_json::Error> {
+    serde_json::from_str(mobiles_json)
+}
+
+/// Loads the mobiles file of every zone that has one, pairing each definition
+/// with the id of the zone it spawns in. A missing file is an error, as for
+/// [`crate::zone::load_rooms`].
+pub fn load_zone_mobiles(
+    zones: &[ZoneInfo],
+    zone_files: &[(&str, &str)],
+) -> Result<Vec<(MobileDef, String)>, String> {
+    let mut defs = Vec::new();
+
+    for zone_info in zones {
+        let Some(file) = &zone_info.mobiles else {
+            continue;
+        };
+        let json_data = zone_file(zone_files, &zone_info.id, file)?;
+
+        for def in load_mobiles(json_data).map_err(|e| format!("{file}: {e}"))? {
+            defs.push((def, zone_info.id.clone()));
+        }
+    }
+
+    Ok(defs)
+}
+
+/// A living NPC somewhere in the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mobile {
+    pub def_id: u32,
+    pub hp: i32,
+    pub location: RoomLocation,
+    /// The room of the reset that spawned the mobile.
+    pub home: RoomLocation,
+}
+
+impl Mobile {
+    pub fn spawn(def: &MobileDef, location: RoomLocation) -> Self {
+        Self {
+            def_id: def.id,
+            hp: def.max_hp,
+            home: location.clone(),
+            location,
+        }
+    }
+}
+
+pub type MobileMap = HashMap<MobileId, Mobile>;
+
+/// Ids of the mobiles in `room_key`, in a stable order so that `2.rat`
+/// always means the same rat.
+pub fn mobiles_in_room(mobiles: &MobileMap, room_key: &str) -> Vec<MobileId> {
+    let mut ids: Vec<MobileId> = mobiles
+        .iter()
+        .filter(|(_, mobile)| mobile.location.to_key() == room_key)
+        .map(|(&id, _)| id)
+        .collect();
+    ids.sort_unstable();
+    ids
+}
+
+/// Finds the mobile in `room_key` named by `target`, honoring `N.name`
+/// ordinals.
+pub fn find_mobile(
+    target: &str,
+    room_key: &str,
+    mobiles: &MobileMap,
+    defs: &MobileDefMap,
+) -> Option<MobileId> {
+    let (ordinal, name) = parse_ordinal(target);
+    mobiles_in_room(mobiles, room_key)
+        .into_iter()
+        .filter(|id| {
+            defs.get(&mobiles[id].def_id)
+                .is_some_and(|def| def.matches(name))
+        })
+        .nth(ordinal - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_mobiles_with_defaults() {
+        let defs = load_mobiles(
+            r#"[{
+                "id": 1,
+                "name": "rat",
+                "keywords": ["rat", "sewer"],
+                "short_description": "a sewer rat",
+                "long_description": "A sewer rat sniffs around here.",
+                "description": "It is mangy.",
+                "level": 1,
+                "max_hp": 6,
+                "damage": [1, 2],
+                "rooms": [3]
+            }]"#,
+        )
+        .unwrap();
+
+        assert_eq!(defs[0].armor, 10);
+        assert_eq!(defs[0].attack_verb, "hits");
+        assert!(!defs[0].aggressive);
+        assert!(!defs[0].wanders);
+        assert!(defs[0].matches("sew ra"));
+    }
+}
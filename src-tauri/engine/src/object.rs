@@ -0,0 +1,184 @@
+use crate::stats::Attribute;
+use crate::text_utils::keywords_match;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectFlag {
+    NoTake,
+    Hidden,
+    Glow,
+    Hum,
+    Magic,
+    /// Which equipment slot the object fits; see [`crate::equipment::WearSlot`].
+    WearHead,
+    WearNeck,
+    WearBody,
+    WearArms,
+    WearHands,
+    WearLegs,
+    WearFeet,
+    WearShield,
+    Wield,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ObjectDef {
+    pub id: u32,
+    pub name: String,
+    pub keywords: Vec<String>,
+    pub short_description: String,
+    pub long_description: String,
+    #[serde(default)]
+    pub examine_description: Option<String>,
+    #[serde(default)]
+    pub flags: Vec<ObjectFlag>,
+    /// Worth in coins, before a shop's markup. Shops won't buy worthless
+    /// objects.
+    #[serde(default)]
+    pub value: u32,
+    /// Bonuses while the object is worn or wielded.
+    #[serde(default)]
+    pub modifiers: HashMap<Attribute, i32>,
+    #[serde(default)]
+    pub armor: i32,
+    /// Damage range of a wielded weapon, inclusive.
+    #[serde(default)]
+    pub damage: Option<(i32, i32)>,
+}
+
+impl ObjectDef {
+    pub fn has_flag(&self, flag: ObjectFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    /// Line shown for this object when it is lying in a room.
+    pub fn room_line(&self) -> String {
+        let mut line = self.long_description.clone();
+        if self.has_flag(ObjectFlag::Glow) {
+            line.push_str(" (glowing)");
+        }
+        if self.has_flag(ObjectFlag::Hum) {
+            line.push_str(" (humming)");
+        }
+        line
+    }
+
+    /// Whether `name` refers to this object; see [`keywords_match`].
+    pub fn matches(&self, name: &str) -> bool {
+        keywords_match(&self.keywords, name)
+    }
+}
+
+pub type ObjectMap = HashMap<u32, ObjectDef>;
+
+pub fn load_objects(objects_json: &str) -> Result<ObjectMap, serde_json::Error> {
+    let objects: Vec<ObjectDef> = serde_json::from_str(objects_json)?;
+    Ok(objects.into_iter().map(|obj| (obj.id, obj)).collect())
+}
+
+/// Splits an ordinal prefix off a target name, so `2.sword` becomes
+/// `(2, "sword")`. Targets without a valid ordinal default to the first match.
+pub fn parse_ordinal(target: &str) -> (usize, &str) {
+    if let Some((count, name)) = target.split_once('.') {
+        if let Ok(count) = count.parse::<usize>() {
+            if count > 0 {
+                return (count, name);
+            }
+        }
+    }
+    (1, target)
+}
+
+/// Finds the index in `object_ids` of the object named by `target`, honoring
+/// `N.name` ordinals. Hidden objects are skipped when `visible_only` is set.
+pub fn find_object(
+    target: &str,
+    object_ids: &[u32],
+    objects: &ObjectMap,
+    visible_only: bool,
+) -> Option<usize> {
+    let (ordinal, name) = parse_ordinal(target);
+    object_ids
+        .iter()
+        .enumerate()
+        .filter(|(_, id)| {
+            objects.get(id).is_some_and(|obj| {
+                !(visible_only && obj.has_flag(ObjectFlag::Hidden)) && obj.matches(name)
+            })
+        })
+        .nth(ordinal - 1)
+        .map(|(index, _)| index)
+}
+
+/// Room lines for every visible object in `object_ids`, skipping unknown ids.
+pub fn describe_room_objects(object_ids: &[u32], objects: &ObjectMap) -> Vec<String> {
+    object_ids
+        .iter()
+        .filter_map(|id| objects.get(id))
+        .filter(|obj| !obj.has_flag(ObjectFlag::Hidden))
+        .map(ObjectDef::room_line)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_OBJECTS: &str = r#"[
+        {
+            "id": 1,
+            "name": "a rusty sword",
+            "keywords": ["sword", "rusty"],
+            "short_description": "a rusty sword",
+            "long_description": "A rusty sword lies here.",
+            "flags": ["glow"]
+        },
+        {
+            "id": 2,
+            "name": "a trapdoor",
+            "keywords": ["trapdoor"],
+            "short_description": "a trapdoor",
+            "long_description": "A trapdoor is set into the floor.",
+            "flags": ["hidden", "no_take"]
+        },
+        {
+            "id": 3,
+            "name": "a pebble",
+            "keywords": ["pebble"],
+            "short_description": "a pebble",
+            "long_description": "A small pebble lies here."
+        }
+    ]"#;
+
+    #[test]
+    fn test_describe_room_objects() {
+        let objects = load_objects(TEST_OBJECTS).unwrap();
+        assert_eq!(objects.len(), 3);
+        assert!(objects[&3].flags.is_empty());
+
+        let lines = describe_room_objects(&[1, 2, 3, 99], &objects);
+        assert_eq!(
+            lines,
+            vec![
+                "A rusty sword lies here. (glowing)".to_string(),
+                "A small pebble lies here.".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_object_with_ordinals() {
+        let objects = load_objects(TEST_OBJECTS).unwrap();
+        let ids = [3, 1, 2, 1];
+
+        assert_eq!(find_object("sw", &ids, &objects, true), Some(1));
+        assert_eq!(find_object("rusty sword", &ids, &objects, true), Some(1));
+        assert_eq!(find_object("2.sword", &ids, &objects, true), Some(3));
+        assert_eq!(find_object("3.sword", &ids, &objects, true), None);
+        assert_eq!(find_object("trapdoor", &ids, &objects, true), None);
+        assert_eq!(find_object("trapdoor", &ids, &objects, false), Some(2));
+        assert_eq!(find_object("", &ids, &objects, true), None);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::room::{Room, RoomLocation};
+use crate::zone::RoomMap;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+
+/// One move of a route: the exit to take and the room it leads to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RouteStep {
+    pub direction: String,
+    pub room_key: String,
+}
+
+/// The shortest route from `from` to the nearest room `is_goal` accepts,
+/// following every visible exit, across zones too. Doors are assumed to
+/// open; walking the route stops at any that don't. The route is empty when
+/// `from` itself is a goal.
+pub fn shortest_route(
+    rooms: &RoomMap,
+    from: &RoomLocation,
+    is_goal: impl Fn(&str, &Room) -> bool,
+) -> Option<Vec<RouteStep>> {
+    let start = from.to_key();
+    // Each reached room with the step that first reached it
+    let mut came_from: HashMap<String, Option<(String, RouteStep)>> = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    came_from.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(room_key) = queue.pop_front() {
+        let Some((room, zone)) = rooms.get(&room_key) else {
+            continue;
+        };
+
+        if is_goal(&room_key, room) {
+            let mut route = Vec::new();
+            let mut key = room_key;
+            while let Some(Some((previous, step))) = came_from.remove(&key) {
+                route.push(step);
+                key = previous;
+            }
+            route.reverse();
+            return Some(route);
+        }
+
+        // Sorted so that equally short routes always come out the same
+        let mut exits: Vec<_> = room.exits.iter().filter(|(_, exit)| !exit.hidden).collect();
+        exits.sort_by_key(|(direction, _)| direction.as_str());
+
+        for (direction, exit) in exits {
+            let Ok(next) = RoomLocation::parse(&exit.to, zone) else {
+                continue;
+            };
+            let next_key = next.to_key();
+            if came_from.contains_key(&next_key) || !rooms.contains_key(&next_key) {
+                continue;
+            }
+
+            let step = RouteStep {
+                direction: direction.clone(),
+                room_key: next_key.clone(),
+            };
+            came_from.insert(next_key.clone(), Some((room_key.clone(), step)));
+            queue.push_back(next_key);
+        }
+    }
+
+    None
+}
+
+/// The route to the room `target` names: a room key such as `millhaven:8`,
+/// a room id in the current zone, or part of a room's name, in which case
+/// the nearest matching room is chosen.
+pub fn find_route(rooms: &RoomMap, from: &RoomLocation, target: &str) -> Option<Vec<RouteStep>> {
+    let target = target.trim();
+    if target.is_empty() {
+        return None;
+    }
+
+    if let Ok(location) = RoomLocation::parse(target, &from.zone) {
+        let key = location.to_key();
+        if rooms.contains_key(&key) {
+            return shortest_route(rooms, from, |room_key, _| room_key == key);
+        }
+    }
+
+    let name = target.to_lowercase();
+    shortest_route(rooms, from, |_, room| {
+        room.name.to_lowercase().contains(&name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::Exit;
+
+    fn room(id: u32, name: &str, exits: &[(&str, &str)]) -> Room {
+        Room {
+            id,
+            name: name.to_string(),
+            description: String::new(),
+            night_description: None,
+            exits: exits
+                .iter()
+                .map(|(direction, to)| (direction.to_string(), Exit::new(*to)))
+                .collect(),
+            objects: vec![],
+        }
+    }
+
+    #[test]
+    fn test_routes_cross_zones_and_prefer_the_nearest_match() {
+        let mut rooms = RoomMap::new();
+        for (zone, room) in [
+            ("town", room(0, "Square", &[("north", "1"), ("east", "2")])),
+            (
+                "town",
+                room(1, "North Gate", &[("south", "0"), ("north", "woods:0")]),
+            ),
+            ("town", room(2, "East Gate", &[("west", "0")])),
+            ("woods", room(0, "Clearing", &[("south", "town:1")])),
+        ] {
+            rooms.insert(format!("{zone}:{}", room.id), (room, zone.to_string()));
+        }
+        let square = RoomLocation {
+            zone: "town".to_string(),
+            room_id: 0,
+        };
+        let directions = |route: Option<Vec<RouteStep>>| -> Option<Vec<String>> {
+            Some(route?.into_iter().map(|step| step.direction).collect())
+        };
+
+        assert_eq!(
+            find_route(&rooms, &square, "woods:0").unwrap(),
+            [
+                RouteStep {
+                    direction: "north".to_string(),
+                    room_key: "town:1".to_string()
+                },
+                RouteStep {
+                    direction: "north".to_string(),
+                    room_key: "woods:0".to_string()
+                },
+            ]
+        );
+        assert_eq!(
+            directions(find_route(&rooms, &square, "2")),
+            Some(vec!["east".to_string()])
+        );
+        // Both gates are one step away; exits are tried alphabetically
+        assert_eq!(
+            directions(find_route(&rooms, &square, "gate")),
+            Some(vec!["east".to_string()])
+        );
+        assert_eq!(
+            directions(find_route(&rooms, &square, "square")),
+            Some(vec![])
+        );
+        assert_eq!(find_route(&rooms, &square, "castle"), None);
+    }
+}
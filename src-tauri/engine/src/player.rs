@@ -0,0 +1,110 @@
+use crate::equipment::Equipment;
+use crate::mobile::MobileId;
+use crate::room::RoomLocation;
+use crate::stats::Stats;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub type PlayerId = u64;
+
+pub const DEFAULT_PLAYER_NAME: &str = "Adventurer";
+const MIN_NAME_LEN: usize = 2;
+const MAX_NAME_LEN: usize = 16;
+/// Coins a new character starts out with.
+pub const STARTING_GOLD: u32 = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Player {
+    #[serde(default = "default_name")]
+    pub(crate) name: String,
+    pub(crate) current_location: RoomLocation,
+    #[serde(default)]
+    pub(crate) inventory: Vec<u32>,
+    /// Race and class ids chosen during character creation.
+    #[serde(default)]
+    pub(crate) race: Option<String>,
+    #[serde(default)]
+    pub(crate) class: Option<String>,
+    #[serde(default)]
+    pub(crate) stats: Stats,
+    /// Characters saved before they carried coins get the starting purse.
+    #[serde(default = "default_gold")]
+    pub(crate) gold: u32,
+    #[serde(default)]
+    pub(crate) equipment: Equipment,
+    /// Keys of every room the player has been in.
+    #[serde(default)]
+    pub(crate) explored: BTreeSet<String>,
+    /// The mobile this player is in combat with. Fights are not saved.
+    #[serde(skip)]
+    pub(crate) fighting: Option<MobileId>,
+}
+
+fn default_name() -> String {
+    DEFAULT_PLAYER_NAME.to_string()
+}
+
+fn default_gold() -> u32 {
+    STARTING_GOLD
+}
+
+impl Player {
+    pub fn new(name: String, zone: String, room_id: u32) -> Self {
+        let current_location = RoomLocation { zone, room_id };
+        Self {
+            name,
+            explored: BTreeSet::from([current_location.to_key()]),
+            current_location,
+            inventory: Vec::new(),
+            race: None,
+            class: None,
+            stats: Stats::default(),
+            gold: STARTING_GOLD,
+            equipment: Equipment::new(),
+            fighting: None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn location(&self) -> &RoomLocation {
+        &self.current_location
+    }
+
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+
+    pub fn gold(&self) -> u32 {
+        self.gold
+    }
+
+    pub fn explored(&self) -> &BTreeSet<String> {
+        &self.explored
+    }
+
+    pub fn move_to(&mut self, location: RoomLocation) {
+        self.explored.insert(location.to_key());
+        self.current_location = location;
+    }
+}
+
+/// Checks a requested character name and returns it capitalized the way it
+/// will be shown to other players.
+pub fn validate_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+
+    if name.len() < MIN_NAME_LEN || name.len() > MAX_NAME_LEN {
+        return Err(format!(
+            "Names must be between {MIN_NAME_LEN} and {MAX_NAME_LEN} letters long."
+        ));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("Names may only contain letters.".to_string());
+    }
+
+    let lower = name.to_ascii_lowercase();
+    Ok(lower[..1].to_ascii_uppercase() + &lower[1..])
+}
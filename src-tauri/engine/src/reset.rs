@@ -0,0 +1,170 @@
+use crate::mobile::MobileDef;
+use crate::zone::{RoomMap, ZoneInfo};
+use serde::{Deserialize, Serialize};
+
+/// One step of a zone reset, in the spirit of DikuMUD zone commands. Room
+/// ids belong to the zone the reset is declared in. Every command only
+/// fills in what is missing, so resetting a zone nobody has touched changes
+/// nothing.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ResetCommand {
+    /// Spawns mobile `mobile` in `room` until `max` of them call it home.
+    Mobile {
+        mobile: u32,
+        room: u32,
+        #[serde(default = "one")]
+        max: usize,
+    },
+    /// Puts object `object` in `room` until `max` of them lie there.
+    Object {
+        object: u32,
+        room: u32,
+        #[serde(default = "one")]
+        max: usize,
+    },
+    /// Sets the door on the `direction` exit of `room`, and its far side.
+    Door {
+        room: u32,
+        direction: String,
+        #[serde(default)]
+        closed: bool,
+        #[serde(default)]
+        locked: bool,
+    },
+    /// Hides the `direction` exit of `room` again after a search found it.
+    Hide { room: u32, direction: String },
+}
+
+fn one() -> usize {
+    1
+}
+
+/// When a zone resets and what it puts back.
+#[derive(Debug, Clone)]
+pub struct ZoneReset {
+    pub zone: String,
+    /// Game minutes between resets; 0 means the zone is only set up once.
+    pub interval: u64,
+    /// Game minute of the next reset.
+    pub next_due: u64,
+    pub commands: Vec<ResetCommand>,
+}
+
+impl ZoneReset {
+    pub fn is_due(&self, now: u64) -> bool {
+        self.interval > 0 && now >= self.next_due
+    }
+}
+
+/// Builds the reset list of every zone. Besides the zone's own `resets`,
+/// each object a room starts with, each exit that starts hidden and each
+/// room a mobile lists in `rooms` counts as a reset, so content written
+/// without resets still respawns.
+/// `now` is the current game minute.
+pub fn zone_resets(
+    zones: &[ZoneInfo],
+    rooms: &RoomMap,
+    mobiles: &[(MobileDef, String)],
+    now: u64,
+) -> Vec<ZoneReset> {
+    zones
+        .iter()
+        .map(|zone_info| {
+            let mut commands = Vec::new();
+
+            let mut zone_rooms: Vec<_> = rooms
+                .values()
+                .filter(|(_, zone)| *zone == zone_info.id)
+                .map(|(room, _)| room)
+                .collect();
+            zone_rooms.sort_by_key(|room| room.id);
+            for room in zone_rooms {
+                let mut objects = room.objects.clone();
+                objects.sort_unstable();
+                objects.dedup();
+                commands.extend(objects.into_iter().map(|object| ResetCommand::Object {
+                    object,
+                    room: room.id,
+                    max: room.objects.iter().filter(|&&id| id == object).count(),
+                }));
+
+                let mut hidden: Vec<&String> = room
+                    .exits
+                    .iter()
+                    .filter(|(_, exit)| exit.hidden)
+                    .map(|(direction, _)| direction)
+                    .collect();
+                hidden.sort();
+                commands.extend(hidden.into_iter().map(|direction| ResetCommand::Hide {
+                    room: room.id,
+                    direction: direction.clone(),
+                }));
+            }
+
+            for (def, _) in mobiles.iter().filter(|(_, zone)| *zone == zone_info.id) {
+                let mut spawn_rooms = def.rooms.clone();
+                spawn_rooms.sort_unstable();
+                spawn_rooms.dedup();
+                commands.extend(spawn_rooms.into_iter().map(|room| ResetCommand::Mobile {
+                    mobile: def.id,
+                    room,
+                    max: def.rooms.iter().filter(|&&id| id == room).count(),
+                }));
+            }
+
+            commands.extend(zone_info.resets.iter().cloned());
+
+            ZoneReset {
+                zone: zone_info.id.clone(),
+                interval: zone_info.reset_minutes,
+                next_due: now + zone_info.reset_minutes,
+                commands,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_commands_parse_with_defaults() {
+        let commands: Vec<ResetCommand> = serde_json::from_str(
+            r#"[
+                {"command": "mobile", "mobile": 2, "room": 13},
+                {"command": "object", "object": 5, "room": 3, "max": 2},
+                {"command": "door", "room": 6, "direction": "east", "closed": true},
+                {"command": "hide", "room": 7, "direction": "west"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            commands,
+            [
+                ResetCommand::Mobile {
+                    mobile: 2,
+                    room: 13,
+                    max: 1
+                },
+                ResetCommand::Object {
+                    object: 5,
+                    room: 3,
+                    max: 2
+                },
+                ResetCommand::Door {
+                    room: 6,
+                    direction: "east".to_string(),
+                    closed: true,
+                    locked: false
+                },
+                ResetCommand::Hide {
+                    room: 7,
+                    direction: "west".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::clock::TimeOfDay;
+use crate::text_utils::format_list;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Room {
+    pub id: u32,
+    pub name: String,
+    pub description: String,
+    /// Shown instead of `description` between sunset and sunrise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub night_description: Option<String>,
+    #[serde(deserialize_with = "deserialize_exits")]
+    pub exits: HashMap<String, Exit>,
+    pub objects: Vec<u32>,
+}
+
+impl Room {
+    /// The description matching the time of day.
+    pub fn description_at(&self, time: TimeOfDay) -> &str {
+        match (time, &self.night_description) {
+            (TimeOfDay::Night, Some(night)) => night,
+            _ => &self.description,
+        }
+    }
+}
+
+/// A way out of a room. In zone files an exit is either just its target
+/// (`"north": "1"`) or an object with a `to` target and optional door and
+/// flags.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Exit {
+    pub to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub door: Option<Door>,
+    /// Not listed or usable until someone searches the room.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub hidden: bool,
+    /// There is no way back, so door changes are not mirrored on the far side.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub one_way: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct Door {
+    #[serde(default = "default_door_name")]
+    pub name: String,
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub locked: bool,
+    /// Object id of the key that locks and unlocks this door. Doors without a
+    /// key can be opened and closed but never locked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<u32>,
+}
+
+fn default_door_name() -> String {
+    "door".to_string()
+}
+
+impl Exit {
+    pub fn new(to: impl Into<String>) -> Self {
+        Self {
+            to: to.into(),
+            door: None,
+            hidden: false,
+            one_way: false,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.door.as_ref().is_some_and(|door| door.closed)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExitSpec {
+    Target(String),
+    Detailed(Exit),
+}
+
+fn deserialize_exits<'de, D>(deserializer: D) -> Result<HashMap<String, Exit>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let specs = HashMap::<String, ExitSpec>::deserialize(deserializer)?;
+    Ok(specs
+        .into_iter()
+        .map(|(direction, spec)| {
+            let exit = match spec {
+                ExitSpec::Target(to) => Exit::new(to),
+                ExitSpec::Detailed(exit) => exit,
+            };
+            (direction, exit)
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RoomLocation {
+    pub zone: String,
+    pub room_id: u32,
+}
+
+impl RoomLocation {
+    pub fn parse(exit: &str, current_zone: &str) -> Result<Self, String> {
+        let (zone, room) = exit.split_once(':').unwrap_or((current_zone, exit));
+
+        if zone.is_empty() {
+            return Err(format!("exit target '{exit}' has an empty zone"));
+        }
+
+        let room_id = room
+            .parse()
+            .map_err(|_| format!("exit target '{exit}' is not a room id"))?;
+
+        Ok(Self {
+            zone: zone.to_string(),
+            room_id,
+        })
+    }
+
+    pub fn to_key(&self) -> String {
+        format!("{}:{}", self.zone, self.room_id)
+    }
+}
+
+pub fn format_exits(exits: &HashMap<String, Exit>) -> String {
+    let exit_names: Vec<String> = exits
+        .iter()
+        .filter(|(_, exit)| !exit.hidden)
+        .map(|(s, _)| format!("**{s}**"))
+        .collect();
+    if exit_names.is_empty() {
+        return String::new();
+    }
+
+    let formatted_list = format_list(&exit_names);
+
+    if exit_names.len() == 1 {
+        format!("There is an available exit to the {formatted_list}.")
+    } else {
+        format!("There are available exits to the {formatted_list}.")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exits_load_from_strings_and_objects() {
+        let room: Room = serde_json::from_str(
+            r#"{"id": 0, "name": "Hall", "description": "", "objects": [], "exits": {
+                "north": "1",
+                "east": {"to": "2", "door": {"name": "gate", "closed": true, "locked": true, "key": 7}},
+                "down": {"to": "cellar:0", "hidden": true, "one_way": true}
+            }}"#,
+        )
+        .unwrap();
+
+        assert_eq!(room.exits["north"], Exit::new("1"));
+        assert_eq!(
+            room.exits["east"].door,
+            Some(Door {
+                name: "gate".to_string(),
+                closed: true,
+                locked: true,
+                key: Some(7),
+            })
+        );
+        assert!(room.exits["down"].hidden && room.exits["down"].one_way);
+        assert_eq!(
+            format_exits(&room.exits).matches("**").count(),
+            4,
+            "hidden exits are not listed"
+        );
+    }
+}
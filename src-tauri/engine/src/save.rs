@@ -0,0 +1,336 @@
+use crate::mobile::Mobile;
+use crate::player::Player;
+use crate::zone::RoomMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever the save layout changes. Older saves still load, with
+/// whatever they lack left as the world has it:
+///
+/// 1. The player and room contents.
+/// 2. Door and hidden exit state.
+/// 3. Mobiles, the clock and zone reset timers.
+pub const SAVE_VERSION: u32 = 3;
+pub const DEFAULT_SLOT: &str = "quicksave";
+pub const AUTOSAVE_SLOT: &str = "autosave";
+const MAX_SLOT_NAME_LEN: usize = 32;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub saved_at: String,
+    pub player: Player,
+    /// Object contents of every room, keyed by `zone:id`.
+    pub room_objects: BTreeMap<String, Vec<u32>>,
+    /// Door and hidden state of every exit, keyed by `zone:id` and then
+    /// direction.
+    #[serde(default)]
+    pub exit_states: BTreeMap<String, BTreeMap<String, ExitState>>,
+    /// Everything else the world changes as it runs. Captured by the game
+    /// rather than [`SaveGame::capture`], since it needs the whole world.
+    #[serde(default)]
+    pub world: Option<WorldState>,
+}
+
+/// The living mobiles and the time everything is measured against.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldState {
+    /// Game minutes since the first minute of year 1.
+    pub minutes: u64,
+    pub mobiles: Vec<Mobile>,
+    /// Game minute each zone next resets at, by zone id.
+    pub reset_due: BTreeMap<String, u64>,
+}
+
+/// The parts of an exit players can change: its door, if it has one, and
+/// whether it has been found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitState {
+    #[serde(default)]
+    pub closed: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SaveSlotInfo {
+    pub slot: String,
+    pub saved_at: String,
+    pub location: String,
+}
+
+impl SaveGame {
+    pub fn capture(player: &Player, rooms: &RoomMap) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            saved_at: chrono::Local::now().to_rfc3339(),
+            player: player.clone(),
+            room_objects: rooms
+                .iter()
+                .map(|(key, (room, _))| (key.clone(), room.objects.clone()))
+                .collect(),
+            exit_states: rooms
+                .iter()
+                .filter(|(_, (room, _))| !room.exits.is_empty())
+                .map(|(key, (room, _))| {
+                    let exits = room
+                        .exits
+                        .iter()
+                        .map(|(direction, exit)| {
+                            let state = ExitState {
+                                closed: exit.is_closed(),
+                                locked: exit.door.as_ref().is_some_and(|door| door.locked),
+                                hidden: exit.hidden,
+                            };
+                            (direction.clone(), state)
+                        })
+                        .collect();
+                    (key.clone(), exits)
+                })
+                .collect(),
+            world: None,
+        }
+    }
+
+    /// Replaces the player, room contents and exit state with the saved
+    /// state. Rooms and exits that no longer exist in the loaded zones are
+    /// ignored.
+    pub fn apply(self, player: &mut Player, rooms: &mut RoomMap) -> Result<(), String> {
+        let location_key = self.player.current_location.to_key();
+        if !rooms.contains_key(&location_key) {
+            return Err(format!("Saved location {location_key} no longer exists."));
+        }
+
+        for (key, objects) in self.room_objects {
+            if let Some((room, _)) = rooms.get_mut(&key) {
+                room.objects = objects;
+            }
+        }
+        for (key, states) in self.exit_states {
+            let Some((room, _)) = rooms.get_mut(&key) else {
+                continue;
+            };
+            for (direction, state) in states {
+                let Some(exit) = room.exits.get_mut(&direction) else {
+                    continue;
+                };
+                exit.hidden = state.hidden;
+                if let Some(door) = exit.door.as_mut() {
+                    door.closed = state.closed;
+                    door.locked = state.locked;
+                }
+            }
+        }
+        *player = self.player;
+        // Saves from before rooms were tracked still know where they are
+        player.explored.insert(location_key);
+
+        Ok(())
+    }
+
+    pub fn load_from_path(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let save: Self = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+
+        if save.version > SAVE_VERSION {
+            return Err(format!(
+                "Save was written by a newer version of the game (format {}).",
+                save.version
+            ));
+        }
+
+        Ok(save)
+    }
+
+    pub fn save_to_path(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, json).map_err(|e| e.to_string())?;
+
+        Ok(())
+    }
+}
+
+/// Resolves a slot name to its file, rejecting names that could escape the
+/// saves directory. Names are matched ignoring case, so a slot keeps the
+/// name it was first saved under.
+pub fn slot_path(saves_dir: &Path, slot: &str) -> Result<PathBuf, String> {
+    let valid = !slot.is_empty()
+        && slot.len() <= MAX_SLOT_NAME_LEN
+        && slot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+    if !valid {
+        return Err(format!(
+            "Invalid save slot '{slot}'. Use up to {MAX_SLOT_NAME_LEN} letters, digits, '-' or '_'."
+        ));
+    }
+
+    let existing = fs::read_dir(saves_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|path| {
+            path.extension().is_some_and(|ext| ext == "json")
+                && path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| stem.eq_ignore_ascii_case(slot))
+        });
+    Ok(existing.unwrap_or_else(|| saves_dir.join(format!("{slot}.json"))))
+}
+
+/// The slot a save file belongs to, as [`list_slots`] shows it.
+pub fn slot_name(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+pub fn list_slots(saves_dir: &Path) -> Vec<SaveSlotInfo> {
+    let Ok(entries) = fs::read_dir(saves_dir) else {
+        return Vec::new();
+    };
+
+    let mut slots: Vec<SaveSlotInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let slot = slot_name(&path);
+            let save = SaveGame::load_from_path(&path).ok()?;
+            Some(SaveSlotInfo {
+                slot,
+                saved_at: save.saved_at,
+                location: save.player.current_location.to_key(),
+            })
+        })
+        .collect();
+
+    slots.sort_by(|a, b| a.slot.cmp(&b.slot));
+    slots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::STARTING_GOLD;
+    use crate::room::{Door, Exit, Room};
+    use std::collections::HashMap;
+
+    fn test_rooms() -> RoomMap {
+        // A locked door from room 0 and a hidden way back from room 1
+        let door = Exit {
+            door: Some(Door {
+                name: "door".to_string(),
+                closed: true,
+                locked: true,
+                key: Some(10),
+            }),
+            ..Exit::new("1")
+        };
+        let secret = Exit {
+            hidden: true,
+            ..Exit::new("0")
+        };
+
+        let mut rooms = HashMap::new();
+        for (id, direction, exit) in [(0, "east", door), (1, "west", secret)] {
+            let room = Room {
+                id,
+                name: format!("Room {id}"),
+                description: "Test room".to_string(),
+                night_description: None,
+                exits: HashMap::from([(direction.to_string(), exit)]),
+                objects: vec![id + 10],
+            };
+            rooms.insert(format!("test:{id}"), (room, "test".to_string()));
+        }
+        rooms
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let dir = std::env::temp_dir().join(format!("muddy-save-test-{}", std::process::id()));
+        let path = slot_path(&dir, "slot_1").unwrap();
+
+        let mut rooms = test_rooms();
+        let mut player = Player::new("Tester".to_string(), "test".to_string(), 1);
+        player.inventory.push(10);
+        let (room, _) = rooms.get_mut("test:0").unwrap();
+        room.objects.clear();
+        let door = room.exits.get_mut("east").unwrap().door.as_mut().unwrap();
+        door.locked = false;
+        door.closed = false;
+        let (room, _) = rooms.get_mut("test:1").unwrap();
+        room.exits.get_mut("west").unwrap().hidden = false;
+        SaveGame::capture(&player, &rooms)
+            .save_to_path(&path)
+            .unwrap();
+
+        let mut restored_rooms = test_rooms();
+        let mut restored_player = Player::new("Tester".to_string(), "test".to_string(), 0);
+        SaveGame::load_from_path(&path)
+            .unwrap()
+            .apply(&mut restored_player, &mut restored_rooms)
+            .unwrap();
+
+        assert_eq!(restored_player.current_location.to_key(), "test:1");
+        assert_eq!(restored_player.inventory, vec![10]);
+        assert!(restored_player.explored().contains("test:1"));
+        assert!(!restored_player.explored().contains("test:0"));
+        assert!(restored_rooms["test:0"].0.objects.is_empty());
+        let door = &restored_rooms["test:0"].0.exits["east"].door;
+        assert!(door
+            .as_ref()
+            .is_some_and(|door| !door.closed && !door.locked));
+        assert!(!restored_rooms["test:1"].0.exits["west"].hidden);
+        assert_eq!(list_slots(&dir)[0].slot, "slot_1");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_version_1_saves_still_load() {
+        let save: SaveGame = serde_json::from_str(
+            r#"{
+                "version": 1,
+                "saved_at": "2024-01-01T00:00:00+00:00",
+                "player": {"name": "Tester", "current_location": {"zone": "test", "room_id": 1}},
+                "room_objects": {"test:0": []}
+            }"#,
+        )
+        .unwrap();
+        assert!(save.exit_states.is_empty());
+
+        let mut rooms = test_rooms();
+        let mut player = Player::new("Tester".to_string(), "test".to_string(), 0);
+        save.apply(&mut player, &mut rooms).unwrap();
+
+        assert_eq!(player.current_location.to_key(), "test:1");
+        assert_eq!(player.gold(), STARTING_GOLD);
+        assert!(rooms["test:0"].0.objects.is_empty());
+        // The world's own door state stays as it was
+        assert!(rooms["test:0"].0.exits["east"].is_closed());
+    }
+
+    #[test]
+    fn test_slot_names_cannot_escape_saves_dir() {
+        let dir = Path::new("saves");
+        assert!(slot_path(dir, "../settings").is_err());
+        assert!(slot_path(dir, "").is_err());
+        assert!(slot_path(dir, "my-save_2").is_ok());
+    }
+}
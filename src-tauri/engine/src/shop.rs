@@ -0,0 +1,182 @@
+use crate::mobile::{mobiles_in_room, MobileDef, MobileDefMap, MobileMap};
+use crate::object::{find_object, ObjectDef, ObjectMap};
+use crate::player::Player;
+use crate::text_utils::capitalize;
+use serde::{Deserialize, Serialize};
+
+/// What a shopkeeper mobile trades in, from the `shop` entry of its
+/// definition. Stock never runs out, and whatever players sell is gone.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct Shop {
+    /// Object ids the shop sells.
+    #[serde(default)]
+    pub inventory: Vec<u32>,
+    /// Times an object's value that players pay for it.
+    #[serde(default = "default_sell_markup")]
+    pub sell_markup: f64,
+    /// Times an object's value that the shop pays players for it.
+    #[serde(default = "default_buy_markup")]
+    pub buy_markup: f64,
+}
+
+fn default_sell_markup() -> f64 {
+    1.2
+}
+
+fn default_buy_markup() -> f64 {
+    0.5
+}
+
+impl Shop {
+    /// What the shop charges for `object`, never less than a coin.
+    pub fn selling_price(&self, object: &ObjectDef) -> u32 {
+        ((object.value as f64 * self.sell_markup).round() as u32).max(1)
+    }
+
+    /// What the shop pays for `object`; 0 means it won't buy it.
+    pub fn buying_price(&self, object: &ObjectDef) -> u32 {
+        (object.value as f64 * self.buy_markup).floor() as u32
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShopAction {
+    List,
+    Buy,
+    Sell,
+    Value,
+}
+
+/// The first shopkeeper in `room_key`, with their shop.
+pub fn find_shopkeeper<'a>(
+    room_key: &str,
+    mobiles: &MobileMap,
+    defs: &'a MobileDefMap,
+) -> Option<(&'a MobileDef, &'a Shop)> {
+    mobiles_in_room(mobiles, room_key)
+        .into_iter()
+        .filter_map(|id| defs.get(&mobiles[&id].def_id))
+        .find_map(|def| def.shop.as_ref().map(|shop| (def, shop)))
+}
+
+/// Trades with the shopkeeper in the player's room.
+pub fn process_shop(
+    player: &mut Player,
+    mobiles: &MobileMap,
+    defs: &MobileDefMap,
+    objects: &ObjectMap,
+    action: ShopAction,
+    target: &str,
+) -> Vec<String> {
+    let Some((keeper, shop)) = find_shopkeeper(&player.current_location.to_key(), mobiles, defs)
+    else {
+        return vec!["There is no shopkeeper here.".to_string()];
+    };
+    let name = capitalize(&keeper.short_description);
+
+    if action == ShopAction::List {
+        let mut lines = vec![format!("{name} has for sale:")];
+        let wares: Vec<String> = shop
+            .inventory
+            .iter()
+            .filter_map(|id| objects.get(id))
+            .map(|obj| {
+                format!(
+                    "  {} - {}",
+                    obj.short_description,
+                    coins(shop.selling_price(obj))
+                )
+            })
+            .collect();
+        if wares.is_empty() {
+            lines.push("  Nothing.".to_string());
+        } else {
+            lines.extend(wares);
+        }
+        return lines;
+    }
+
+    if action == ShopAction::Buy {
+        let Some(index) = find_object(target, &shop.inventory, objects, false) else {
+            return vec![format!("{name} doesn't sell '{target}'.")];
+        };
+        let object_id = shop.inventory[index];
+        let object = &objects[&object_id];
+        let price = shop.selling_price(object);
+        if player.gold < price {
+            return vec![format!(
+                "You can't afford {}. It costs {}.",
+                object.short_description,
+                coins(price)
+            )];
+        }
+
+        player.gold -= price;
+        player.inventory.push(object_id);
+        return vec![format!(
+            "You buy {} for {}.",
+            object.short_description,
+            coins(price)
+        )];
+    }
+
+    let Some(index) = find_object(target, &player.inventory, objects, false) else {
+        return vec![format!("You aren't carrying '{target}'.")];
+    };
+    let object = &objects[&player.inventory[index]];
+    let price = shop.buying_price(object);
+    if price == 0 {
+        return vec![format!(
+            "{name} isn't interested in {}.",
+            object.short_description
+        )];
+    }
+
+    if action == ShopAction::Value {
+        return vec![format!(
+            "{name} would give you {} for {}.",
+            coins(price),
+            object.short_description
+        )];
+    }
+
+    player.inventory.remove(index);
+    player.gold += price;
+    vec![format!(
+        "You sell {} for {}.",
+        object.short_description,
+        coins(price)
+    )]
+}
+
+pub fn coins(amount: u32) -> String {
+    match amount {
+        1 => "1 coin".to_string(),
+        _ => format!("{amount} coins"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::object::load_objects;
+
+    #[test]
+    fn test_prices_follow_value_and_markups() {
+        let objects = load_objects(
+            r#"[
+                {"id": 1, "name": "sword", "keywords": ["sword"], "short_description": "a sword",
+                 "long_description": "A sword lies here.", "value": 25},
+                {"id": 2, "name": "pebble", "keywords": ["pebble"], "short_description": "a pebble",
+                 "long_description": "A pebble lies here."}
+            ]"#,
+        )
+        .unwrap();
+        let shop: Shop = serde_json::from_str(r#"{"inventory": [1, 2]}"#).unwrap();
+
+        assert_eq!(shop.selling_price(&objects[&1]), 30);
+        assert_eq!(shop.buying_price(&objects[&1]), 12);
+        assert_eq!(shop.selling_price(&objects[&2]), 1);
+        assert_eq!(shop.buying_price(&objects[&2]), 0);
+    }
+}
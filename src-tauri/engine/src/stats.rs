@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+pub const STARTING_HP: i32 = 20;
+pub const STARTING_MANA: i32 = 10;
+pub const STARTING_STAMINA: i32 = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Attributes {
+    pub strength: u32,
+    pub dexterity: u32,
+    pub constitution: u32,
+    pub intelligence: u32,
+    pub wisdom: u32,
+    pub charisma: u32,
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Self {
+            strength: 10,
+            dexterity: 10,
+            constitution: 10,
+            intelligence: 10,
+            wisdom: 10,
+            charisma: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Attribute {
+    Strength,
+    Dexterity,
+    Constitution,
+    Intelligence,
+    Wisdom,
+    Charisma,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 6] = [
+        Attribute::Strength,
+        Attribute::Dexterity,
+        Attribute::Constitution,
+        Attribute::Intelligence,
+        Attribute::Wisdom,
+        Attribute::Charisma,
+    ];
+
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Attribute::Strength => "Str",
+            Attribute::Dexterity => "Dex",
+            Attribute::Constitution => "Con",
+            Attribute::Intelligence => "Int",
+            Attribute::Wisdom => "Wis",
+            Attribute::Charisma => "Cha",
+        }
+    }
+}
+
+impl Attributes {
+    pub fn get(&self, attribute: Attribute) -> u32 {
+        match attribute {
+            Attribute::Strength => self.strength,
+            Attribute::Dexterity => self.dexterity,
+            Attribute::Constitution => self.constitution,
+            Attribute::Intelligence => self.intelligence,
+            Attribute::Wisdom => self.wisdom,
+            Attribute::Charisma => self.charisma,
+        }
+    }
+
+    pub fn get_mut(&mut self, attribute: Attribute) -> &mut u32 {
+        match attribute {
+            Attribute::Strength => &mut self.strength,
+            Attribute::Dexterity => &mut self.dexterity,
+            Attribute::Constitution => &mut self.constitution,
+            Attribute::Intelligence => &mut self.intelligence,
+            Attribute::Wisdom => &mut self.wisdom,
+            Attribute::Charisma => &mut self.charisma,
+        }
+    }
+
+    /// All six scores on one line, e.g. `Str 14  Dex 12  ...`.
+    pub fn summary(&self) -> String {
+        Attribute::ALL
+            .iter()
+            .map(|&attribute| format!("{} {:>2}", attribute.abbreviation(), self.get(attribute)))
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
+/// The bonus (or penalty) an attribute score gives: 0 at 10, +1 for every
+/// two points above it.
+pub fn modifier(score: u32) -> i32 {
+    (score as i32 - 10).div_euclid(2)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub level: u32,
+    pub xp: u32,
+    pub hp: i32,
+    pub max_hp: i32,
+    pub mana: i32,
+    pub max_mana: i32,
+    pub stamina: i32,
+    pub max_stamina: i32,
+    pub attributes: Attributes,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            level: 1,
+            xp: 0,
+            hp: STARTING_HP,
+            max_hp: STARTING_HP,
+            mana: STARTING_MANA,
+            max_mana: STARTING_MANA,
+            stamina: STARTING_STAMINA,
+            max_stamina: STARTING_STAMINA,
+            attributes: Attributes::default(),
+        }
+    }
+}
+
+/// Total experience needed to advance past `level`.
+pub fn xp_for_next_level(level: u32) -> u32 {
+    100 * level * level
+}
+
+impl Stats {
+    pub fn restore(&mut self) {
+        self.hp = self.max_hp;
+        self.mana = self.max_mana;
+        self.stamina = self.max_stamina;
+    }
+
+    /// Recovers a tenth of each maximum, at least one point, up to the
+    /// maximum.
+    pub fn regenerate(&mut self) {
+        for (current, max) in [
+            (&mut self.hp, self.max_hp),
+            (&mut self.mana, self.max_mana),
+            (&mut self.stamina, self.max_stamina),
+        ] {
+            *current = (*current + (max / 10).max(1)).min(max);
+        }
+    }
+
+    /// Adds experience, levelling up as many times as it allows. Each level
+    /// raises the maximums by an amount that grows with the matching
+    /// attribute, and fully restores the character.
+    pub fn gain_xp(&mut self, amount: u32) -> Vec<String> {
+        self.xp += amount;
+        let mut lines = vec![format!("You receive {amount} experience points.")];
+
+        while self.xp >= xp_for_next_level(self.level) {
+            self.level += 1;
+            self.max_hp += (6 + modifier(self.attributes.constitution)).max(1);
+            self.max_mana += (3 + modifier(self.attributes.intelligence)).max(1);
+            self.max_stamina += (4 + modifier(self.attributes.dexterity)).max(1);
+            self.restore();
+            lines.push(format!("You have reached level {}!", self.level));
+        }
+
+        lines
+    }
+
+    /// The status prompt shown after every command.
+    pub fn prompt(&self) -> String {
+        format!(
+            "<{}/{}hp {}/{}m {}/{}mv>",
+            self.hp, self.max_hp, self.mana, self.max_mana, self.stamina, self.max_stamina
+        )
+    }
+
+    /// `title` names the character, e.g. `Alice the Dwarf Warrior`.
+    pub fn score_sheet(&self, title: &str) -> Vec<String> {
+        let a = &self.attributes;
+        vec![
+            format!("**{title}**, level {}", self.level),
+            format!(
+                "  HP: {}/{}   Mana: {}/{}   Stamina: {}/{}",
+                self.hp, self.max_hp, self.mana, self.max_mana, self.stamina, self.max_stamina
+            ),
+            format!(
+                "  Experience: {} ({} needed for level {})",
+                self.xp,
+                xp_for_next_level(self.level),
+                self.level + 1
+            ),
+            String::new(),
+            format!(
+                "  Str: {:>2}   Dex: {:>2}   Con: {:>2}",
+                a.strength, a.dexterity, a.constitution
+            ),
+            format!(
+                "  Int: {:>2}   Wis: {:>2}   Cha: {:>2}",
+                a.intelligence, a.wisdom, a.charisma
+            ),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gain_xp_levels_up_and_restores() {
+        let mut stats = Stats {
+            hp: 3,
+            ..Stats::default()
+        };
+        stats.attributes.constitution = 14;
+
+        let lines = stats.gain_xp(450);
+
+        assert_eq!(stats.level, 3);
+        assert_eq!(stats.max_hp, STARTING_HP + 2 * 8);
+        assert_eq!(stats.hp, stats.max_hp);
+        assert_eq!(
+            lines,
+            vec![
+                "You receive 450 experience points.",
+                "You have reached level 2!",
+                "You have reached level 3!",
+            ]
+        );
+        assert_eq!(modifier(7), -2);
+    }
+}
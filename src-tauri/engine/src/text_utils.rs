@@ -35,6 +35,18 @@ pub fn wrap_lines(text: &str, line_length: usize) -> Vec<String> {
     lines
 }
 
+/// Classic MUD name matching: every word of `name` must be a prefix of one
+/// of `keywords`, so `sw` and `rusty sw` both match a rusty sword.
+pub fn keywords_match(keywords: &[String], name: &str) -> bool {
+    let mut words = name.split_whitespace().peekable();
+    words.peek().is_some()
+        && words.all(|word| {
+            keywords
+                .iter()
+                .any(|keyword| keyword.to_lowercase().starts_with(word))
+        })
+}
+
 pub fn format_list(items: &[String]) -> String {
     match items.len() {
         0 => String::new(),
@@ -46,3 +58,35 @@ pub fn format_list(items: &[String]) -> String {
         }
     }
 }
+
+pub fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Turns `**bold**` markup into ANSI bold escapes, or strips it when `color`
+/// is off. Spans may cross newlines; an unclosed span is reset at the end.
+pub fn markup_to_ansi(text: &str, color: bool) -> String {
+    const BOLD: &str = "\x1b[1m";
+    const RESET: &str = "\x1b[0m";
+
+    let mut output = String::with_capacity(text.len());
+    let mut bold = false;
+    for (index, part) in text.split("**").enumerate() {
+        if index > 0 {
+            bold = !bold;
+            if color {
+                output.push_str(if bold { BOLD } else { RESET });
+            }
+        }
+        output.push_str(part);
+    }
+    if bold && color {
+        output.push_str(RESET);
+    }
+
+    output
+}
@@ -0,0 +1,122 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::{interval, MissedTickBehavior};
+
+pub const DEFAULT_TICK_RATE: Duration = Duration::from_secs(2);
+/// Faster ticks than this would mostly spend their time waiting on locks.
+pub const MIN_TICK_RATE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct TickState {
+    paused: bool,
+    rate: Duration,
+}
+
+/// Pauses, resumes and re-times a running [`run_ticks`] loop. Clones all
+/// control the same loop.
+#[derive(Debug, Clone)]
+pub struct TickControl {
+    state: Arc<watch::Sender<TickState>>,
+}
+
+impl TickControl {
+    pub fn new(rate: Duration) -> Self {
+        let (state, _) = watch::channel(TickState {
+            paused: false,
+            rate: rate.max(MIN_TICK_RATE),
+        });
+        Self {
+            state: Arc::new(state),
+        }
+    }
+
+    pub fn pause(&self) {
+        self.state
+            .send_if_modified(|state| !std::mem::replace(&mut state.paused, true));
+    }
+
+    pub fn resume(&self) {
+        self.state
+            .send_if_modified(|state| std::mem::replace(&mut state.paused, false));
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().paused
+    }
+
+    /// Changes the time between ticks. The next tick comes one full new
+    /// period after the change.
+    pub fn set_rate(&self, rate: Duration) {
+        let rate = rate.max(MIN_TICK_RATE);
+        self.state
+            .send_if_modified(|state| std::mem::replace(&mut state.rate, rate) != rate);
+    }
+
+    pub fn rate(&self) -> Duration {
+        self.state.borrow().rate
+    }
+}
+
+impl Default for TickControl {
+    fn default() -> Self {
+        Self::new(DEFAULT_TICK_RATE)
+    }
+}
+
+/// Calls `on_tick` once per tick, forever, with the game time the tick
+/// stands for. Spawn it on whichever tokio runtime the frontend runs.
+pub async fn run_ticks(control: TickControl, mut on_tick: impl FnMut(Duration)) {
+    let mut changes = control.state.subscribe();
+
+    loop {
+        let state = *changes.borrow_and_update();
+        if state.paused {
+            // The sender lives in `control`, so this only waits for a change
+            let _ = changes.changed().await;
+            continue;
+        }
+
+        let mut ticks = interval(state.rate);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick of an interval fires straight away
+        ticks.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = ticks.tick() => on_tick(state.rate),
+                _ = changes.changed() => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::time::sleep;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause_resume_and_rate_change() {
+        let control = TickControl::new(Duration::from_secs(1));
+        let count = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&count);
+        tokio::spawn(run_ticks(control.clone(), move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        // With time paused, sleeping runs every tick that falls due meanwhile
+        sleep(Duration::from_millis(3500)).await;
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        control.pause();
+        sleep(Duration::from_secs(10)).await;
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        control.resume();
+        control.set_rate(Duration::from_millis(250));
+        sleep(Duration::from_millis(1100)).await;
+        assert_eq!(count.load(Ordering::SeqCst), 7);
+    }
+}
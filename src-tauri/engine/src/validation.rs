@@ -0,0 +1,630 @@
+use crate::mobile::load_mobiles;
+use crate::object::load_objects;
+use crate::reset::ResetCommand;
+use crate::room::{Exit, Room, RoomLocation};
+use crate::zone::{ZoneConfig, OBJECTS_FILE, ZONES_FILE};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCode {
+    InvalidZonesConfig,
+    DuplicateZoneId,
+    MissingZoneFile,
+    InvalidZoneFile,
+    InvalidRoom,
+    DuplicateRoomId,
+    InvalidExit,
+    UnparsableExit,
+    UnknownExitZone,
+    MissingExitRoom,
+    MissingInitialRoom,
+    UnreachableRoom,
+    InvalidMobileFile,
+    DuplicateMobileId,
+    MissingMobileRoom,
+    InvalidReset,
+    InvalidObjectFile,
+    UnknownObject,
+}
+
+impl IssueCode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IssueCode::InvalidZonesConfig => "invalid_zones_config",
+            IssueCode::DuplicateZoneId => "duplicate_zone_id",
+            IssueCode::MissingZoneFile => "missing_zone_file",
+            IssueCode::InvalidZoneFile => "invalid_zone_file",
+            IssueCode::InvalidRoom => "invalid_room",
+            IssueCode::DuplicateRoomId => "duplicate_room_id",
+            IssueCode::InvalidExit => "invalid_exit",
+            IssueCode::UnparsableExit => "unparsable_exit",
+            IssueCode::UnknownExitZone => "unknown_exit_zone",
+            IssueCode::MissingExitRoom => "missing_exit_room",
+            IssueCode::MissingInitialRoom => "missing_initial_room",
+            IssueCode::UnreachableRoom => "unreachable_room",
+            IssueCode::InvalidMobileFile => "invalid_mobile_file",
+            IssueCode::DuplicateMobileId => "duplicate_mobile_id",
+            IssueCode::MissingMobileRoom => "missing_mobile_room",
+            IssueCode::InvalidReset => "invalid_reset",
+            IssueCode::InvalidObjectFile => "invalid_object_file",
+            IssueCode::UnknownObject => "unknown_object",
+        }
+    }
+
+    pub fn severity(self) -> Severity {
+        match self {
+            IssueCode::UnreachableRoom => Severity::Warning,
+            _ => Severity::Error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub code: IssueCode,
+    /// Where the problem is, e.g. `millhaven.json` or `millhaven:3 exit north`.
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{severity}[{}] {}: {}",
+            self.code.as_str(),
+            self.location,
+            self.message
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    fn push(&mut self, code: IssueCode, location: impl Into<String>, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            severity: code.severity(),
+            code,
+            location: location.into(),
+            message: message.into(),
+        });
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.issues.len() - self.error_count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in &self.issues {
+            writeln!(f, "{issue}")?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.error_count(),
+            self.warning_count()
+        )
+    }
+}
+
+/// Room as seen by the validator: exits keep their raw JSON values so that
+/// malformed exits can be reported instead of failing the whole file.
+struct RawRoom {
+    zone: String,
+    id: u32,
+    exits: Vec<(String, Value)>,
+    objects: Vec<u32>,
+}
+
+/// Ids from the object catalog, or `None` when it could not be read, in
+/// which case object references go unchecked.
+type ObjectIds = Option<HashSet<u32>>;
+
+/// Reports `object` as unknown unless the catalog has it.
+fn check_object(
+    object_ids: &ObjectIds,
+    object: u32,
+    location: impl Into<String>,
+    what: &str,
+    report: &mut ValidationReport,
+) {
+    if object_ids
+        .as_ref()
+        .is_some_and(|ids| !ids.contains(&object))
+    {
+        report.push(
+            IssueCode::UnknownObject,
+            location,
+            format!("{what} unknown object {object}"),
+        );
+    }
+}
+
+/// Checks a `zones.json` and its zone files, taking the same in-memory inputs
+/// as [`crate::zone::load_rooms`], along with the `objects.json` catalog that
+/// rooms, doors, mobiles and resets refer to.
+pub fn validate_zones(
+    zones_json: &str,
+    zone_files: &[(&str, &str)],
+    objects_json: &str,
+) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    let object_ids: ObjectIds = match load_objects(objects_json) {
+        Ok(objects) => Some(objects.into_keys().collect()),
+        Err(e) => {
+            report.push(IssueCode::InvalidObjectFile, OBJECTS_FILE, e.to_string());
+            None
+        }
+    };
+
+    let zone_config: ZoneConfig = match serde_json::from_str(zones_json) {
+        Ok(config) => config,
+        Err(e) => {
+            report.push(IssueCode::InvalidZonesConfig, ZONES_FILE, e.to_string());
+            return report;
+        }
+    };
+
+    let mut zone_ids = HashSet::new();
+    let mut rooms: Vec<RawRoom> = Vec::new();
+    let mut room_keys = HashSet::new();
+
+    for zone_info in &zone_config.zones {
+        if !zone_ids.insert(zone_info.id.as_str()) {
+            report.push(
+                IssueCode::DuplicateZoneId,
+                ZONES_FILE,
+                format!("zone id '{}' is declared more than once", zone_info.id),
+            );
+            continue;
+        }
+
+        let Some(&(_, json_data)) = zone_files.iter().find(|(file, _)| *file == zone_info.file)
+        else {
+            report.push(
+                IssueCode::MissingZoneFile,
+                &zone_info.file,
+                format!(
+                    "zone '{}' points to a file that was not found",
+                    zone_info.id
+                ),
+            );
+            continue;
+        };
+
+        let entries: Vec<Value> = match serde_json::from_str(json_data) {
+            Ok(entries) => entries,
+            Err(e) => {
+                report.push(IssueCode::InvalidZoneFile, &zone_info.file, e.to_string());
+                continue;
+            }
+        };
+
+        for (index, mut entry) in entries.into_iter().enumerate() {
+            // Pull the exits out first so that a bad exit value does not hide
+            // every other problem in the room
+            let raw_exits: Vec<(String, Value)> = match entry.get_mut("exits") {
+                Some(Value::Object(exits)) => std::mem::take(exits).into_iter().collect(),
+                _ => Vec::new(),
+            };
+
+            let room: Room = match serde_json::from_value(entry) {
+                Ok(room) => room,
+                Err(e) => {
+                    report.push(
+                        IssueCode::InvalidRoom,
+                        format!("{} room #{index}", zone_info.file),
+                        e.to_string(),
+                    );
+                    continue;
+                }
+            };
+
+            let key = format!("{}:{}", zone_info.id, room.id);
+            if !room_keys.insert(key.clone()) {
+                report.push(
+                    IssueCode::DuplicateRoomId,
+                    key,
+                    format!("room id {} is used more than once", room.id),
+                );
+                continue;
+            }
+
+            rooms.push(RawRoom {
+                zone: zone_info.id.clone(),
+                id: room.id,
+                exits: raw_exits,
+                objects: room.objects,
+            });
+        }
+    }
+
+    let mobile_ids = validate_mobiles(
+        &zone_config,
+        zone_files,
+        &room_keys,
+        &object_ids,
+        &mut report,
+    );
+    validate_resets(&zone_config, &rooms, &mobile_ids, &object_ids, &mut report);
+
+    let mut graph: HashMap<String, Vec<String>> = HashMap::new();
+    for room in &rooms {
+        let key = format!("{}:{}", room.zone, room.id);
+        let mut neighbours = Vec::new();
+
+        for &object in &room.objects {
+            let location = format!("{key} objects");
+            check_object(&object_ids, object, location, "room holds", &mut report);
+        }
+
+        for (direction, value) in &room.exits {
+            let location = format!("{key} exit {direction}");
+
+            let target = match value {
+                Value::String(target) => target.clone(),
+                Value::Object(_) => match serde_json::from_value::<Exit>(value.clone()) {
+                    Ok(exit) => {
+                        if let Some(key) = exit.door.and_then(|door| door.key) {
+                            let what = "door is locked with";
+                            check_object(&object_ids, key, &location, what, &mut report);
+                        }
+                        exit.to
+                    }
+                    Err(e) => {
+                        report.push(IssueCode::InvalidExit, location, e.to_string());
+                        continue;
+                    }
+                },
+                _ => {
+                    report.push(
+                        IssueCode::InvalidExit,
+                        location,
+                        format!("exit must be a target string or an object, found {value}"),
+                    );
+                    continue;
+                }
+            };
+
+            let destination = match RoomLocation::parse(&target, &room.zone) {
+                Ok(destination) => destination,
+                Err(e) => {
+                    report.push(IssueCode::UnparsableExit, location, e);
+                    continue;
+                }
+            };
+
+            if !zone_ids.contains(destination.zone.as_str()) {
+                report.push(
+                    IssueCode::UnknownExitZone,
+                    location,
+                    format!("exit leads to unknown zone '{}'", destination.zone),
+                );
+            } else if !room_keys.contains(&destination.to_key()) {
+                report.push(
+                    IssueCode::MissingExitRoom,
+                    location,
+                    format!("exit leads to missing room {}", destination.to_key()),
+                );
+            } else {
+                neighbours.push(destination.to_key());
+            }
+        }
+
+        graph.insert(key, neighbours);
+    }
+
+    let initial_key = format!("{}:{}", zone_config.initial_zone, zone_config.initial_room);
+    if !room_keys.contains(&initial_key) {
+        report.push(
+            IssueCode::MissingInitialRoom,
+            ZONES_FILE,
+            format!("initial room {initial_key} does not exist"),
+        );
+        return report;
+    }
+
+    let mut reached = HashSet::from([initial_key.clone()]);
+    let mut queue = VecDeque::from([initial_key]);
+    while let Some(key) = queue.pop_front() {
+        for next in graph.get(&key).into_iter().flatten() {
+            if reached.insert(next.clone()) {
+                queue.push_back(next.clone());
+            }
+        }
+    }
+
+    for room in &rooms {
+        let key = format!("{}:{}", room.zone, room.id);
+        if !reached.contains(&key) {
+            report.push(
+                IssueCode::UnreachableRoom,
+                key,
+                "room cannot be reached from the initial room",
+            );
+        }
+    }
+
+    report
+}
+
+fn validate_mobiles(
+    zone_config: &ZoneConfig,
+    zone_files: &[(&str, &str)],
+    room_keys: &HashSet<String>,
+    object_ids: &ObjectIds,
+    report: &mut ValidationReport,
+) -> HashSet<u32> {
+    let mut mobile_ids = HashSet::new();
+
+    for zone_info in &zone_config.zones {
+        let Some(file) = &zone_info.mobiles else {
+            continue;
+        };
+
+        let Some(&(_, json_data)) = zone_files.iter().find(|(name, _)| name == file) else {
+            report.push(
+                IssueCode::MissingZoneFile,
+                file,
+                format!(
+                    "zone '{}' points to a mobiles file that was not found",
+                    zone_info.id
+                ),
+            );
+            continue;
+        };
+
+        let defs = match load_mobiles(json_data) {
+            Ok(defs) => defs,
+            Err(e) => {
+                report.push(IssueCode::InvalidMobileFile, file, e.to_string());
+                continue;
+            }
+        };
+
+        for def in defs {
+            let location = format!("{file} mobile {}", def.id);
+            if !mobile_ids.insert(def.id) {
+                report.push(
+                    IssueCode::DuplicateMobileId,
+                    location,
+                    format!("mobile id {} is used more than once", def.id),
+                );
+                continue;
+            }
+
+            for room_id in &def.rooms {
+                let key = format!("{}:{room_id}", zone_info.id);
+                if !room_keys.contains(&key) {
+                    report.push(
+                        IssueCode::MissingMobileRoom,
+                        location.clone(),
+                        format!("{} spawns in missing room {key}", def.name),
+                    );
+                }
+            }
+
+            for entry in &def.loot {
+                check_object(object_ids, entry.object, &location, "drops", report);
+            }
+            for &object in def.shop.iter().flat_map(|shop| &shop.inventory) {
+                check_object(object_ids, object, &location, "sells", report);
+            }
+        }
+    }
+
+    mobile_ids
+}
+
+/// Checks that reset commands name rooms, mobiles, objects and doors that
+/// exist.
+fn validate_resets(
+    zone_config: &ZoneConfig,
+    rooms: &[RawRoom],
+    mobile_ids: &HashSet<u32>,
+    object_ids: &ObjectIds,
+    report: &mut ValidationReport,
+) {
+    for zone_info in &zone_config.zones {
+        for (index, reset) in zone_info.resets.iter().enumerate() {
+            let location = format!("{ZONES_FILE} zone {} reset #{index}", zone_info.id);
+            let room_id = match reset {
+                ResetCommand::Mobile { room, .. }
+                | ResetCommand::Object { room, .. }
+                | ResetCommand::Door { room, .. }
+                | ResetCommand::Hide { room, .. } => *room,
+            };
+            let Some(room) = rooms
+                .iter()
+                .find(|room| room.zone == zone_info.id && room.id == room_id)
+            else {
+                report.push(
+                    IssueCode::InvalidReset,
+                    location,
+                    format!("reset refers to missing room {}:{room_id}", zone_info.id),
+                );
+                continue;
+            };
+
+            match reset {
+                ResetCommand::Mobile { mobile, .. } if !mobile_ids.contains(mobile) => {
+                    report.push(
+                        IssueCode::InvalidReset,
+                        location,
+                        format!("reset loads unknown mobile {mobile}"),
+                    );
+                }
+                ResetCommand::Object { object, .. } => {
+                    check_object(object_ids, *object, location, "reset loads", report);
+                }
+                ResetCommand::Door { direction, .. } => {
+                    let has_door = room
+                        .exits
+                        .iter()
+                        .any(|(name, exit)| name == direction && exit.get("door").is_some());
+                    if !has_door {
+                        report.push(
+                            IssueCode::InvalidReset,
+                            location,
+                            format!(
+                                "room {}:{room_id} has no door to the {direction}",
+                                zone_info.id
+                            ),
+                        );
+                    }
+                }
+                ResetCommand::Hide { direction, .. }
+                    if !room.exits.iter().any(|(name, _)| name == direction) =>
+                {
+                    report.push(
+                        IssueCode::InvalidReset,
+                        location,
+                        format!(
+                            "room {}:{room_id} has no exit to the {direction}",
+                            zone_info.id
+                        ),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZONES: &str = r#"{
+        "zones": [
+            {"id": "town", "name": "Town", "file": "town.json", "mobiles": "town_mobiles.json",
+             "resets": [
+                {"command": "mobile", "mobile": 7, "room": 0},
+                {"command": "object", "object": 1, "room": 9},
+                {"command": "door", "room": 0, "direction": "north", "closed": true},
+                {"command": "door", "room": 1, "direction": "south", "closed": true},
+                {"command": "object", "object": 4, "room": 0}
+             ]},
+            {"id": "caves", "name": "Caves", "file": "caves.json"}
+        ],
+        "initial_zone": "town",
+        "initial_room": 0
+    }"#;
+
+    const TOWN: &str = r#"[
+        {"id": 0, "name": "Square", "description": "", "objects": [],
+         "exits": {"north": "1", "south": "abc", "east": 5, "west": "forest:0", "down": "7",
+                   "up": {"door": {"name": "hatch"}}}},
+        {"id": 1, "name": "Road", "description": "", "objects": [],
+         "exits": {"south": {"to": "0", "door": {"name": "gate", "closed": true, "key": 8}}}},
+        {"id": 1, "name": "Copy", "description": "", "objects": [], "exits": {}},
+        {"id": 2, "name": "Island", "description": "", "objects": [1, 9], "exits": {}}
+    ]"#;
+
+    const TOWN_MOBILES: &str = r#"[
+        {"id": 1, "name": "rat", "keywords": ["rat"], "short_description": "a rat",
+         "long_description": "", "description": "", "level": 1, "max_hp": 5,
+         "damage": [1, 2], "rooms": [0, 9], "loot": [{"object": 5, "chance": 0.5}],
+         "shop": {"inventory": [1, 6]}},
+        {"id": 1, "name": "cat", "keywords": ["cat"], "short_description": "a cat",
+         "long_description": "", "description": "", "level": 1, "max_hp": 5,
+         "damage": [1, 2], "rooms": [0]}
+    ]"#;
+
+    const OBJECTS: &str = r#"[
+        {"id": 1, "name": "key", "keywords": ["key"], "short_description": "a key",
+         "long_description": "A key lies here."}
+    ]"#;
+
+    fn codes(report: &ValidationReport) -> Vec<IssueCode> {
+        let mut codes: Vec<IssueCode> = report.issues.iter().map(|issue| issue.code).collect();
+        codes.sort_by_key(|code| code.as_str());
+        codes
+    }
+
+    #[test]
+    fn test_reports_broken_zone_content() {
+        let report = validate_zones(
+            ZONES,
+            &[("town.json", TOWN), ("town_mobiles.json", TOWN_MOBILES)],
+            OBJECTS,
+        );
+
+        assert_eq!(
+            codes(&report),
+            vec![
+                IssueCode::DuplicateMobileId,
+                IssueCode::DuplicateRoomId,
+                IssueCode::InvalidExit,
+                IssueCode::InvalidExit,
+                IssueCode::InvalidReset,
+                IssueCode::InvalidReset,
+                IssueCode::InvalidReset,
+                IssueCode::MissingExitRoom,
+                IssueCode::MissingMobileRoom,
+                IssueCode::MissingZoneFile,
+                IssueCode::UnknownExitZone,
+                IssueCode::UnknownObject,
+                IssueCode::UnknownObject,
+                IssueCode::UnknownObject,
+                IssueCode::UnknownObject,
+                IssueCode::UnknownObject,
+                IssueCode::UnparsableExit,
+                IssueCode::UnreachableRoom,
+            ]
+        );
+        assert_eq!(report.error_count(), 17);
+        assert_eq!(report.warning_count(), 1);
+    }
+
+    #[test]
+    fn test_bundled_content_is_clean() {
+        let report = validate_zones(
+            include_str!("../../rooms/zones.json"),
+            &[
+                ("millhaven.json", include_str!("../../rooms/millhaven.json")),
+                (
+                    "millhaven_mobiles.json",
+                    include_str!("../../rooms/millhaven_mobiles.json"),
+                ),
+            ],
+            include_str!("../../rooms/objects.json"),
+        );
+
+        assert!(!report.has_errors(), "{report}");
+    }
+}
@@ -0,0 +1,1461 @@
+use crate::clock::{GameClock, TimeOfDay};
+use crate::combat::{consider_message, swing, xp_reward, Combatant, Swing, FLEE_CHANCE};
+use crate::command_parser::{
+    arrival_message, expand_direction, get_inventory_display, get_room_display, help_text,
+    mirror_door, parse_command, parse_speedwalk, process_door, process_drop, process_examine,
+    process_get, process_move, process_search, CommandError, DoorAction, Verb, MAX_SPEEDWALK_STEPS,
+};
+use crate::creation::{Character, CharacterCreation, CharacterData, CreationResult};
+use crate::equipment::{get_equipment_display, process_remove, process_wear};
+use crate::minimap::{generate_level_minimap, generate_minimap, generate_zone_map, MinimapNode};
+use crate::mobile::{
+    find_mobile, load_zone_mobiles, mobiles_in_room, Mobile, MobileDefMap, MobileId, MobileMap,
+    WANDER_CHANCE,
+};
+use crate::object::{load_objects, ObjectMap};
+use crate::pathfinding::{find_route, RouteStep};
+use crate::player::{Player, PlayerId};
+use crate::reset::{zone_resets, ResetCommand, ZoneReset};
+use crate::room::RoomLocation;
+use crate::save::WorldState;
+use crate::shop::{process_shop, ShopAction};
+use crate::stats::modifier;
+use crate::text_utils::capitalize;
+use crate::validation::{validate_zones, ValidationReport};
+use crate::zone::{
+    load_rooms, read_content_file, read_zone_source, RoomMap, ZoneConfig, CLASSES_FILE,
+    OBJECTS_FILE, RACES_FILE, ZONES_FILE,
+};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Something a client should show or do in response to a command.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "text", rename_all = "snake_case")]
+pub enum Output {
+    /// A line of game text, possibly containing `**bold**` markup.
+    Message(String),
+    /// The player moved or the world changed, so any minimap should be redrawn.
+    MinimapUpdated,
+}
+
+pub(crate) fn messages(lines: Vec<String>) -> Vec<Output> {
+    lines.into_iter().map(Output::Message).collect()
+}
+
+/// Output of a successful move, which also changes the minimap.
+fn moved(lines: Vec<String>) -> Vec<Output> {
+    let mut output = messages(lines);
+    output.push(Output::MinimapUpdated);
+    output
+}
+
+fn name_available(players: &HashMap<PlayerId, Player>, name: &str) -> bool {
+    !players
+        .values()
+        .any(|player| player.name.eq_ignore_ascii_case(name))
+}
+
+/// The banner shown before a player's first room or creation question.
+pub(crate) fn welcome_lines() -> Vec<String> {
+    vec![
+        "=== Welcome to Muddy Rogue ===".to_string(),
+        "Type 'help' for available commands.".to_string(),
+        String::new(),
+    ]
+}
+
+/// The shared game world: rooms, their contents, and every player and NPC
+/// in it.
+///
+/// Commands return output for the player who typed them. Messages meant for
+/// other players, such as someone walking into their room, are queued and
+/// collected with [`World::drain_outbox`].
+pub struct World {
+    pub(crate) rooms: RoomMap,
+    pub(crate) objects: ObjectMap,
+    pub(crate) players: HashMap<PlayerId, Player>,
+    next_player_id: PlayerId,
+    pub(crate) mobile_defs: MobileDefMap,
+    pub(crate) mobiles: MobileMap,
+    next_mobile_id: MobileId,
+    pub(crate) character_data: CharacterData,
+    outbox: Vec<(PlayerId, Output)>,
+    start: RoomLocation,
+    pub(crate) rng: StdRng,
+    clock: GameClock,
+    resets: Vec<ZoneReset>,
+}
+
+impl World {
+    pub fn load_from_zones(
+        zones_json: &str,
+        zone_files: &[(&str, &str)],
+        objects_json: &str,
+    ) -> Result<Self, String> {
+        let zone_config: ZoneConfig =
+            serde_json::from_str(zones_json).map_err(|e| format!("{ZONES_FILE}: {e}"))?;
+        let room = load_rooms(&zone_config.zones, zone_files)?;
+        let objects = load_objects(objects_json).map_err(|e| format!("{OBJECTS_FILE}: {e}"))?;
+        let zone_mobiles = load_zone_mobiles(&zone_config.zones, zone_files)?;
+
+        let mut world = Self {
+            rooms: room,
+            objects,
+            players: HashMap::new(),
+            next_player_id: 1,
+            mobile_defs: HashMap::new(),
+            mobiles: HashMap::new(),
+            next_mobile_id: 1,
+            character_data: CharacterData::default(),
+            outbox: Vec::new(),
+            start: RoomLocation {
+                zone: zone_config.initial_zone,
+                room_id: zone_config.initial_room,
+            },
+            rng: StdRng::from_entropy(),
+            clock: GameClock::new(&zone_config.clock),
+            resets: Vec::new(),
+        };
+
+        world.resets = zone_resets(
+            &zone_config.zones,
+            &world.rooms,
+            &zone_mobiles,
+            world.clock.total_minutes(),
+        );
+        world.mobile_defs = zone_mobiles
+            .into_iter()
+            .map(|(def, _)| (def.id, def))
+            .collect();
+
+        // The first reset populates every zone
+        for index in 0..world.resets.len() {
+            world.reset_zone(index);
+        }
+
+        Ok(world)
+    }
+
+    /// Sets the races and classes offered during character creation.
+    pub fn with_character_data(mut self, character_data: CharacterData) -> Self {
+        self.character_data = character_data;
+        self
+    }
+
+    /// Makes combat and other dice rolls repeatable, for tests and replays.
+    pub fn with_rng_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// Loads and checks the content in `content_dirs`. Errors fail the load;
+    /// the report comes back with any warnings for the caller to show.
+    pub fn load_from_dirs(content_dirs: &[PathBuf]) -> Result<(Self, ValidationReport), String> {
+        let source = read_zone_source(content_dirs)?;
+        let objects_json = read_content_file(content_dirs, OBJECTS_FILE)?;
+
+        let report = validate_zones(&source.zones_json, &source.zone_file_refs(), &objects_json);
+        if report.has_errors() {
+            return Err(format!("Zone validation failed:\n{report}"));
+        }
+
+        let world =
+            Self::load_from_zones(&source.zones_json, &source.zone_file_refs(), &objects_json)?;
+
+        let character_data = CharacterData::load(
+            &read_content_file(content_dirs, RACES_FILE)?,
+            &read_content_file(content_dirs, CLASSES_FILE)?,
+        )
+        .map_err(|e| format!("{RACES_FILE} or {CLASSES_FILE}: {e}"))?;
+        Ok((world.with_character_data(character_data), report))
+    }
+
+    /// Mobiles, the clock and reset timers, for a saved game.
+    pub(crate) fn world_state(&self) -> WorldState {
+        let mut ids: Vec<&MobileId> = self.mobiles.keys().collect();
+        ids.sort_unstable();
+        WorldState {
+            minutes: self.clock.total_minutes(),
+            mobiles: ids.into_iter().map(|id| self.mobiles[id].clone()).collect(),
+            reset_due: self
+                .resets
+                .iter()
+                .map(|reset| (reset.zone.clone(), reset.next_due))
+                .collect(),
+        }
+    }
+
+    /// Puts back the mobiles, clock and reset timers of a saved game. Mobiles
+    /// whose definition or room no longer exists are left out, and fights
+    /// end, since mobiles get new ids.
+    pub(crate) fn restore_world_state(&mut self, state: WorldState) {
+        self.clock.set_total_minutes(state.minutes);
+        for reset in &mut self.resets {
+            if let Some(&due) = state.reset_due.get(&reset.zone) {
+                reset.next_due = due;
+            }
+        }
+
+        self.mobiles.clear();
+        for mobile in state.mobiles {
+            if self.mobile_defs.contains_key(&mobile.def_id)
+                && self.rooms.contains_key(&mobile.location.to_key())
+            {
+                self.mobiles.insert(self.next_mobile_id, mobile);
+                self.next_mobile_id += 1;
+            }
+        }
+        for player in self.players.values_mut() {
+            player.fighting = None;
+        }
+    }
+
+    /// Puts a new player called `name` in the initial room.
+    pub fn add_player(&mut self, name: &str) -> PlayerId {
+        let id = self.next_player_id;
+        self.next_player_id += 1;
+
+        let player = Player::new(
+            name.to_string(),
+            self.start.zone.clone(),
+            self.start.room_id,
+        );
+        self.notify_room(
+            &player.current_location.to_key(),
+            id,
+            format!("{name} has entered the game."),
+        );
+        self.players.insert(id, player);
+        id
+    }
+
+    /// Puts a newly created character in the initial room, with maximums
+    /// raised by their class and attributes.
+    pub fn add_character(&mut self, character: Character) -> PlayerId {
+        let id = self.add_player(&character.name);
+        let class = character
+            .class
+            .as_deref()
+            .and_then(|class| self.character_data.class(class));
+        let player = self.players.get_mut(&id).expect("player was just added");
+
+        let stats = &mut player.stats;
+        stats.max_hp += modifier(character.attributes.constitution) * 2;
+        stats.max_mana += modifier(character.attributes.intelligence) * 2;
+        stats.max_stamina += modifier(character.attributes.dexterity) * 2;
+        if let Some(class) = class {
+            stats.max_hp += class.bonus_hp;
+            stats.max_mana += class.bonus_mana;
+            stats.max_stamina += class.bonus_stamina;
+        }
+        stats.max_hp = stats.max_hp.max(1);
+        stats.attributes = character.attributes;
+        stats.restore();
+
+        player.race = character.race;
+        player.class = character.class;
+        id
+    }
+
+    pub fn remove_player(&mut self, id: PlayerId) -> Option<Player> {
+        let player = self.players.remove(&id)?;
+        self.notify_room(
+            &player.current_location.to_key(),
+            id,
+            format!("{} has left the game.", player.name),
+        );
+        Some(player)
+    }
+
+    pub fn player(&self, id: PlayerId) -> Option<&Player> {
+        self.players.get(&id)
+    }
+
+    /// Whether `name` is free, compared case-insensitively with online players.
+    pub fn name_available(&self, name: &str) -> bool {
+        name_available(&self.players, name)
+    }
+
+    /// The question a player creating a character is being asked.
+    pub fn creation_prompt(&self, creation: &CharacterCreation) -> Vec<String> {
+        creation.prompt(&self.character_data)
+    }
+
+    /// Feeds one line of input to `creation`. If someone else took the name
+    /// while the character was being made, creation starts over instead of
+    /// finishing.
+    pub fn creation_input(
+        &mut self,
+        creation: &mut CharacterCreation,
+        input: &str,
+    ) -> CreationResult {
+        let players = &self.players;
+        let result = creation.handle_input(input, &self.character_data, &mut self.rng, |name| {
+            name_available(players, name)
+        });
+
+        match result {
+            CreationResult::Done(character) if !self.name_available(&character.name) => {
+                *creation = CharacterCreation::new();
+                let mut lines = vec![format!(
+                    "Someone called {} is already playing.",
+                    character.name
+                )];
+                lines.extend(self.creation_prompt(creation));
+                CreationResult::Continue(lines)
+            }
+            result => result,
+        }
+    }
+
+    /// Messages queued for players other than the one acting, and for
+    /// anyone who saw something happen during a tick, in the order they
+    /// happened.
+    pub fn drain_outbox(&mut self) -> Vec<(PlayerId, Output)> {
+        std::mem::take(&mut self.outbox)
+    }
+
+    /// Queues `message` for everyone in the room `room_key` except `actor`.
+    fn notify_room(&mut self, room_key: &str, actor: PlayerId, message: String) {
+        for (&id, player) in &self.players {
+            if id != actor && player.current_location.to_key() == room_key {
+                self.outbox.push((id, Output::Message(message.clone())));
+            }
+        }
+    }
+
+    /// Queues `message` for everyone in the room `room_key`.
+    fn broadcast_room(&mut self, room_key: &str, message: String) {
+        for (&id, player) in &self.players {
+            if player.current_location.to_key() == room_key {
+                self.outbox.push((id, Output::Message(message.clone())));
+            }
+        }
+    }
+
+    /// Lines for the NPCs and other players sharing `id`'s room.
+    fn occupant_lines(&self, id: PlayerId) -> Vec<String> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        let room_key = player.current_location.to_key();
+
+        let mut lines: Vec<String> = mobiles_in_room(&self.mobiles, &room_key)
+            .iter()
+            .filter_map(|mobile_id| self.mobile_defs.get(&self.mobiles[mobile_id].def_id))
+            .map(|def| def.long_description.clone())
+            .collect();
+
+        let mut names: Vec<&str> = self
+            .players
+            .iter()
+            .filter(|(&other, p)| other != id && p.current_location.to_key() == room_key)
+            .map(|(_, p)| p.name.as_str())
+            .collect();
+        names.sort_unstable();
+        lines.extend(names.into_iter().map(|name| format!("{name} is here.")));
+
+        if !lines.is_empty() {
+            lines.insert(0, String::new());
+        }
+        lines
+    }
+
+    pub fn start_messages(&self, id: PlayerId) -> Vec<Output> {
+        let mut lines = welcome_lines();
+        lines.extend(self.get_room_display(id));
+        messages(lines)
+    }
+
+    /// Status line to show after each command, e.g. `<20/20hp 10/10m 40/40mv>`.
+    pub fn prompt(&self, id: PlayerId) -> String {
+        self.players
+            .get(&id)
+            .map(|player| player.stats.prompt())
+            .unwrap_or_default()
+    }
+
+    pub fn minimap(&self, id: PlayerId, max_distance: i32) -> Vec<MinimapNode> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        generate_minimap(&player.current_location, &self.rooms, max_distance)
+    }
+
+    /// The minimap `level` storeys above or below `id`'s; see
+    /// [`generate_level_minimap`].
+    pub fn minimap_level(&self, id: PlayerId, max_distance: i32, level: i32) -> Vec<MinimapNode> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        generate_level_minimap(&player.current_location, &self.rooms, max_distance, level)
+    }
+
+    /// The rooms `id` has explored in `zone`, or their own zone if `None`;
+    /// see [`generate_zone_map`].
+    pub fn zone_map(&self, id: PlayerId, zone: Option<&str>) -> Vec<MinimapNode> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        let zone = zone.unwrap_or(&player.current_location.zone);
+        generate_zone_map(
+            &player.current_location,
+            &player.explored,
+            &self.rooms,
+            zone,
+        )
+    }
+
+    /// The route from `id`'s room to the room `target` names; see
+    /// [`find_route`].
+    pub fn route(&self, id: PlayerId, target: &str) -> Option<Vec<RouteStep>> {
+        let player = self.players.get(&id)?;
+        find_route(&self.rooms, &player.current_location, target)
+    }
+
+    pub fn get_room_display(&self, id: PlayerId) -> Vec<String> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        let mut lines =
+            get_room_display(player, &self.rooms, &self.objects, self.clock.time_of_day());
+        lines.extend(self.occupant_lines(id));
+        lines
+    }
+
+    fn try_move(&mut self, id: PlayerId, command: &str) -> Result<Vec<String>, String> {
+        let player = self
+            .players
+            .get_mut(&id)
+            .ok_or_else(|| "Error: Player not found.".to_string())?;
+        if player.fighting.is_some() {
+            return Err("No way! You're fighting for your life!".to_string());
+        }
+        let from = player.current_location.to_key();
+
+        let mut lines = process_move(
+            player,
+            &self.rooms,
+            &self.objects,
+            self.clock.time_of_day(),
+            command,
+        )?;
+        let to = player.current_location.to_key();
+        let name = player.name.clone();
+        let direction = expand_direction(command);
+
+        self.notify_room(&from, id, format!("{name} leaves {direction}."));
+        self.notify_room(&to, id, arrival_message(&name, direction));
+        lines.extend(self.occupant_lines(id));
+        lines.extend(self.aggressive_attack(id, &to));
+        Ok(lines)
+    }
+
+    /// Lets the first aggressive mobile in `room_key` jump a player who just
+    /// arrived.
+    fn aggressive_attack(&mut self, id: PlayerId, room_key: &str) -> Vec<String> {
+        let attacker = mobiles_in_room(&self.mobiles, room_key)
+            .into_iter()
+            .find(|mobile_id| self.mobile_defs[&self.mobiles[mobile_id].def_id].aggressive);
+        let (Some(mobile_id), Some(player)) = (attacker, self.players.get_mut(&id)) else {
+            return Vec::new();
+        };
+
+        player.fighting = Some(mobile_id);
+        let def = &self.mobile_defs[&self.mobiles[&mobile_id].def_id];
+        vec![
+            String::new(),
+            format!("{} attacks you!", capitalize(&def.short_description)),
+        ]
+    }
+
+    pub fn clock(&self) -> &GameClock {
+        &self.clock
+    }
+
+    /// Runs the world's periodic systems once: game time moves on by
+    /// `elapsed`, wandering mobiles roam, everyone out of combat regenerates
+    /// and zones whose reset is due are repopulated. What players see is
+    /// queued for [`World::drain_outbox`].
+    pub fn tick(&mut self, elapsed: Duration) {
+        self.advance_clock(elapsed);
+        self.fight();
+        self.wander_mobiles();
+        self.regenerate();
+        self.reset_zones();
+    }
+
+    fn advance_clock(&mut self, elapsed: Duration) {
+        let before = self.clock.time_of_day();
+        self.clock.advance(elapsed);
+
+        let message = match (before, self.clock.time_of_day()) {
+            (TimeOfDay::Night, TimeOfDay::Day) => "The sun rises in the east.",
+            (TimeOfDay::Day, TimeOfDay::Night) => "The sun sets in the west.",
+            _ => return,
+        };
+        for &id in self.players.keys() {
+            self.outbox.push((id, Output::Message(message.to_string())));
+        }
+    }
+
+    /// Runs a combat round for every player who is fighting, so foes keep
+    /// swinging whether or not the player types anything.
+    fn fight(&mut self) {
+        let mut fighters: Vec<PlayerId> = self
+            .players
+            .iter()
+            .filter(|(_, player)| player.fighting.is_some())
+            .map(|(&id, _)| id)
+            .collect();
+        fighters.sort_unstable();
+
+        for id in fighters {
+            for output in self.combat_round(id) {
+                self.outbox.push((id, output));
+            }
+        }
+    }
+
+    fn wander_mobiles(&mut self) {
+        // Sorted so that a seeded world wanders the same way every time
+        let mut ids: Vec<MobileId> = self.mobiles.keys().copied().collect();
+        ids.sort_unstable();
+
+        for mobile_id in ids {
+            let mobile = &self.mobiles[&mobile_id];
+            let def = &self.mobile_defs[&mobile.def_id];
+            let in_combat = self
+                .players
+                .values()
+                .any(|player| player.fighting == Some(mobile_id));
+            if !def.wanders || in_combat || !self.rng.gen_bool(WANDER_CHANCE) {
+                continue;
+            }
+
+            let from = mobile.location.to_key();
+            let Some((room, zone)) = self.rooms.get(&from) else {
+                continue;
+            };
+            let mut exits: Vec<(&String, RoomLocation)> = room
+                .exits
+                .iter()
+                .filter(|(_, exit)| !exit.hidden && !exit.is_closed())
+                .filter_map(|(direction, exit)| {
+                    let to = RoomLocation::parse(&exit.to, zone).ok()?;
+                    let stays_in_zone = &to.zone == zone && self.rooms.contains_key(&to.to_key());
+                    stays_in_zone.then_some((direction, to))
+                })
+                .collect();
+            exits.sort_by(|a, b| a.0.cmp(b.0));
+            let Some((direction, to)) = exits.choose(&mut self.rng) else {
+                continue;
+            };
+
+            let direction = direction.to_string();
+            let to = to.clone();
+            let short = capitalize(&def.short_description);
+
+            self.broadcast_room(&from, format!("{short} leaves {direction}."));
+            self.broadcast_room(&to.to_key(), arrival_message(&short, &direction));
+            if let Some(mobile) = self.mobiles.get_mut(&mobile_id) {
+                mobile.location = to;
+            }
+        }
+    }
+
+    fn regenerate(&mut self) {
+        for player in self.players.values_mut() {
+            if player.fighting.is_none() {
+                player.stats.regenerate();
+            }
+        }
+
+        for (mobile_id, mobile) in &mut self.mobiles {
+            let in_combat = self
+                .players
+                .values()
+                .any(|player| player.fighting == Some(*mobile_id));
+            if !in_combat {
+                let max_hp = self.mobile_defs[&mobile.def_id].max_hp;
+                mobile.hp = (mobile.hp + (max_hp / 10).max(1)).min(max_hp);
+            }
+        }
+    }
+
+    fn reset_zones(&mut self) {
+        let now = self.clock.total_minutes();
+        for index in 0..self.resets.len() {
+            if self.resets[index].is_due(now) {
+                self.reset_zone(index);
+                let reset = &mut self.resets[index];
+                reset.next_due = now + reset.interval;
+            }
+        }
+    }
+
+    /// Runs the commands of one zone reset, putting back whatever is missing.
+    fn reset_zone(&mut self, index: usize) {
+        let zone = self.resets[index].zone.clone();
+        let commands = self.resets[index].commands.clone();
+
+        for command in commands {
+            match command {
+                ResetCommand::Mobile { mobile, room, max } => {
+                    let home = RoomLocation {
+                        zone: zone.clone(),
+                        room_id: room,
+                    };
+                    self.reset_mobile(mobile, home, max);
+                }
+                ResetCommand::Object { object, room, max } => {
+                    let key = format!("{zone}:{room}");
+                    let (Some((room, _)), true) =
+                        (self.rooms.get_mut(&key), self.objects.contains_key(&object))
+                    else {
+                        continue;
+                    };
+                    let present = room.objects.iter().filter(|&&id| id == object).count();
+                    room.objects
+                        .extend(std::iter::repeat_n(object, max.saturating_sub(present)));
+                }
+                ResetCommand::Door {
+                    room,
+                    direction,
+                    closed,
+                    locked,
+                } => {
+                    let key = format!("{zone}:{room}");
+                    let Some((room, _)) = self.rooms.get_mut(&key) else {
+                        continue;
+                    };
+                    let Some(exit) = room.exits.get_mut(&direction) else {
+                        continue;
+                    };
+                    let Some(door) = exit.door.as_mut() else {
+                        continue;
+                    };
+                    let closed = closed || locked;
+                    door.closed = closed;
+                    door.locked = locked;
+
+                    if exit.one_way {
+                        continue;
+                    }
+                    if let Ok(far) = RoomLocation::parse(&exit.to, &zone) {
+                        mirror_door(&mut self.rooms, &far.to_key(), &key, closed, locked);
+                    }
+                }
+                ResetCommand::Hide { room, direction } => {
+                    let key = format!("{zone}:{room}");
+                    if let Some(exit) = self
+                        .rooms
+                        .get_mut(&key)
+                        .and_then(|(room, _)| room.exits.get_mut(&direction))
+                    {
+                        exit.hidden = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Spawns mobile `def_id` at `home` until `max` of them live there.
+    fn reset_mobile(&mut self, def_id: u32, home: RoomLocation, max: usize) {
+        let Some(def) = self.mobile_defs.get(&def_id) else {
+            return;
+        };
+        let home_key = home.to_key();
+        let present = self
+            .mobiles
+            .values()
+            .filter(|mobile| mobile.def_id == def_id && mobile.home.to_key() == home_key)
+            .count();
+        if present >= max {
+            return;
+        }
+
+        let arrival = format!("{} appears.", capitalize(&def.short_description));
+        for _ in present..max {
+            self.mobiles
+                .insert(self.next_mobile_id, Mobile::spawn(def, home.clone()));
+            self.next_mobile_id += 1;
+        }
+        self.broadcast_room(&home_key, arrival);
+    }
+
+    pub fn handle_command(&mut self, id: PlayerId, command: &str) -> Vec<Output> {
+        let action = self.is_combat_action(id, command);
+        let mut output = self.dispatch(id, command);
+
+        // Attacking, fleeing or trying to leave gives both sides a swing on
+        // top of the rounds each tick brings
+        if action && self.players.get(&id).is_some_and(|p| p.fighting.is_some()) {
+            output.extend(self.combat_round(id));
+        }
+        output
+    }
+
+    /// Whether `input` attacks, flees or tries to leave. Looking around,
+    /// checking the score sheet and typos don't count.
+    fn is_combat_action(&self, id: PlayerId, input: &str) -> bool {
+        self.has_exit(id, &input.trim().to_lowercase())
+            || matches!(
+                parse_command(input).map(|command| command.verb()),
+                Ok(Verb::Kill | Verb::Flee | Verb::Move | Verb::Travel)
+            )
+    }
+
+    /// Whether `id`'s room has a visible exit called exactly `name`.
+    fn has_exit(&self, id: PlayerId, name: &str) -> bool {
+        self.players
+            .get(&id)
+            .and_then(|player| self.rooms.get(&player.current_location.to_key()))
+            .and_then(|(room, _)| room.exits.get(name))
+            .is_some_and(|exit| !exit.hidden)
+    }
+
+    fn dispatch(&mut self, id: PlayerId, input: &str) -> Vec<Output> {
+        // Rooms may have exits named something other than a direction, and
+        // one named like a command prefix, such as `in`, wins over the command
+        let word = input.trim().to_lowercase();
+        if self.has_exit(id, &word) {
+            return match self.try_move(id, &word) {
+                Ok(lines) => moved(lines),
+                Err(e) => messages(vec![e]),
+            };
+        }
+
+        let command = match parse_command(input) {
+            Ok(command) => command,
+            Err(e) => {
+                // Speedwalks such as `3n2e` aren't commands, and ones like
+                // `n n` read as a move with an argument
+                let speedwalk = match &e {
+                    CommandError::Unknown(_) => true,
+                    CommandError::UnexpectedArgument(spec) => spec.verb == Verb::Move,
+                    _ => false,
+                };
+                if let Some(directions) = speedwalk
+                    .then(|| parse_speedwalk(&input.trim().to_lowercase()))
+                    .flatten()
+                {
+                    if directions.len() > MAX_SPEEDWALK_STEPS {
+                        return messages(vec!["That's too far to walk in one go.".to_string()]);
+                    }
+                    return self.walk(id, &directions);
+                }
+                return messages(vec![e.to_string()]);
+            }
+        };
+        // Names are matched case-insensitively
+        let target = command.target().to_lowercase();
+        let target = target.as_str();
+
+        // Commands that need the whole world rather than just the player
+        match command.verb() {
+            Verb::Move => {
+                return match self.try_move(id, command.spec.name) {
+                    Ok(lines) => moved(lines),
+                    Err(e) => messages(vec![e]),
+                };
+            }
+            Verb::Flee => return self.process_flee(id),
+            Verb::Travel => return self.process_travel(id, target),
+            // `look <thing>` is the same as `examine <thing>`
+            Verb::Examine | Verb::Look if !target.is_empty() => {
+                if let Some(lines) = self.examine_mobile(id, target) {
+                    return messages(lines);
+                }
+            }
+            _ => {}
+        }
+
+        let Some(player) = self.players.get_mut(&id) else {
+            return messages(vec!["Error: Player not found.".to_string()]);
+        };
+
+        // Other commands
+        let lines = match command.verb() {
+            // Handled above
+            Verb::Move | Verb::Travel | Verb::Flee => Vec::new(),
+            Verb::Help => help_text(),
+            Verb::Look if target.is_empty() => self.get_room_display(id),
+            Verb::Get => process_get(player, &mut self.rooms, &self.objects, target),
+            Verb::Drop => process_drop(player, &mut self.rooms, &self.objects, target),
+            Verb::Inventory => get_inventory_display(player, &self.objects),
+            Verb::Examine | Verb::Look => {
+                process_examine(player, &self.rooms, &self.objects, target)
+            }
+            Verb::Open => process_door(player, &mut self.rooms, DoorAction::Open, target),
+            Verb::Close => process_door(player, &mut self.rooms, DoorAction::Close, target),
+            Verb::Lock => process_door(player, &mut self.rooms, DoorAction::Lock, target),
+            Verb::Unlock => process_door(player, &mut self.rooms, DoorAction::Unlock, target),
+            Verb::Search => process_search(player, &mut self.rooms),
+            Verb::Kill => self.process_kill(id, target),
+            Verb::Consider => self.process_consider(id, target),
+            Verb::Score => player.stats.score_sheet(&self.character_data.title(
+                &player.name,
+                player.race.as_deref(),
+                player.class.as_deref(),
+            )),
+            Verb::Time => self.clock.describe(),
+            Verb::Wear => process_wear(player, &self.objects, false, target),
+            Verb::Wield => process_wear(player, &self.objects, true, target),
+            Verb::Remove => process_remove(player, &self.objects, target),
+            Verb::Equipment => get_equipment_display(player, &self.objects),
+            Verb::List | Verb::Buy | Verb::Sell | Verb::Value => {
+                let action = match command.verb() {
+                    Verb::List => ShopAction::List,
+                    Verb::Buy => ShopAction::Buy,
+                    Verb::Sell => ShopAction::Sell,
+                    _ => ShopAction::Value,
+                };
+                process_shop(
+                    player,
+                    &self.mobiles,
+                    &self.mobile_defs,
+                    &self.objects,
+                    action,
+                    target,
+                )
+            }
+            // Save slots belong to the single-player game
+            Verb::Save | Verb::Restore | Verb::Saves => {
+                vec!["Saved games aren't available here.".to_string()]
+            }
+        };
+
+        messages(lines)
+    }
+
+    /// The mobile in `id`'s room that `target` names, if any.
+    fn target_mobile(&self, id: PlayerId, target: &str) -> Option<MobileId> {
+        let player = self.players.get(&id)?;
+        find_mobile(
+            target,
+            &player.current_location.to_key(),
+            &self.mobiles,
+            &self.mobile_defs,
+        )
+    }
+
+    fn examine_mobile(&self, id: PlayerId, target: &str) -> Option<Vec<String>> {
+        let mobile = &self.mobiles[&self.target_mobile(id, target)?];
+        let def = &self.mobile_defs[&mobile.def_id];
+        Some(vec![
+            format!("**{}**", capitalize(&def.short_description)),
+            def.description.clone(),
+        ])
+    }
+
+    fn process_kill(&mut self, id: PlayerId, target: &str) -> Vec<String> {
+        if self.players.get(&id).is_some_and(|p| p.fighting.is_some()) {
+            return vec!["You are already fighting!".to_string()];
+        }
+        let Some(mobile_id) = self.target_mobile(id, target) else {
+            return vec![format!("You don't see '{target}' here.")];
+        };
+
+        let short = self.mobile_defs[&self.mobiles[&mobile_id].def_id]
+            .short_description
+            .clone();
+        let Some(player) = self.players.get_mut(&id) else {
+            return Vec::new();
+        };
+        player.fighting = Some(mobile_id);
+        let (name, room_key) = (player.name.clone(), player.current_location.to_key());
+
+        self.notify_room(&room_key, id, format!("{name} attacks {short}!"));
+        vec![format!("You attack {short}!")]
+    }
+
+    fn process_consider(&self, id: PlayerId, target: &str) -> Vec<String> {
+        let (Some(player), Some(mobile_id)) =
+            (self.players.get(&id), self.target_mobile(id, target))
+        else {
+            return vec![format!("You don't see '{target}' here.")];
+        };
+
+        let def = &self.mobile_defs[&self.mobiles[&mobile_id].def_id];
+        vec![consider_message(
+            player.stats.level,
+            def.level,
+            &def.short_description,
+        )]
+    }
+
+    /// Walks the shortest route to another room.
+    fn process_travel(&mut self, id: PlayerId, target: &str) -> Vec<Output> {
+        let Some(route) = self.route(id, target) else {
+            return messages(vec![format!("You don't know the way to '{target}'.")]);
+        };
+        if route.is_empty() {
+            return messages(vec!["You are already there.".to_string()]);
+        }
+
+        let directions: Vec<&str> = route.iter().map(|step| step.direction.as_str()).collect();
+        self.walk(id, &directions)
+    }
+
+    /// Moves through `directions` one step at a time, naming each room
+    /// passed and describing only the last. Stops at the first exit that
+    /// can't be taken or when something attacks.
+    fn walk(&mut self, id: PlayerId, directions: &[&str]) -> Vec<Output> {
+        if self.players.get(&id).is_some_and(|p| p.fighting.is_some()) {
+            return messages(vec!["No way! You're fighting for your life!".to_string()]);
+        }
+
+        let total = directions.len();
+        let mut lines = Vec::new();
+        for (index, direction) in directions.iter().enumerate() {
+            let room_lines = match self.try_move(id, direction) {
+                Ok(room_lines) => room_lines,
+                Err(e) if index == 0 => return messages(vec![e]),
+                Err(e) => {
+                    lines.push(e);
+                    lines.push(format!("You stop after {index} of {total} steps."));
+                    break;
+                }
+            };
+
+            let taken = index + 1;
+            let fighting = self.players.get(&id).is_some_and(|p| p.fighting.is_some());
+            if taken < total && !fighting {
+                let name = self
+                    .players
+                    .get(&id)
+                    .and_then(|p| self.rooms.get(&p.current_location.to_key()))
+                    .map_or("", |(room, _)| room.name.as_str());
+                lines.push(format!("You go {direction} to {name}."));
+                continue;
+            }
+
+            if taken < total {
+                lines.push(format!("You stop after {taken} of {total} steps."));
+            } else if total > 1 {
+                lines.push(format!("You arrive after {total} steps."));
+            }
+            lines.extend(room_lines);
+            break;
+        }
+
+        moved(lines)
+    }
+
+    fn process_flee(&mut self, id: PlayerId) -> Vec<Output> {
+        let Some(player) = self.players.get(&id) else {
+            return Vec::new();
+        };
+        if player.fighting.is_none() {
+            return messages(vec!["You aren't fighting anyone.".to_string()]);
+        }
+
+        let mut ways_out: Vec<&str> = self
+            .rooms
+            .get(&player.current_location.to_key())
+            .map(|(room, _)| {
+                room.exits
+                    .iter()
+                    .filter(|(_, exit)| !exit.hidden && !exit.is_closed())
+                    .map(|(direction, _)| direction.as_str())
+                    .collect()
+            })
+            .unwrap_or_default();
+        ways_out.sort_unstable();
+
+        let direction = match ways_out.choose(&mut self.rng) {
+            Some(direction) if self.rng.gen_bool(FLEE_CHANCE) => direction.to_string(),
+            _ => return messages(vec!["You panic and fail to flee!".to_string()]),
+        };
+
+        if let Some(player) = self.players.get_mut(&id) {
+            player.fighting = None;
+        }
+        let mut lines = vec![format!("You flee {direction}!"), String::new()];
+        match self.try_move(id, &direction) {
+            Ok(room_lines) => lines.extend(room_lines),
+            Err(e) => lines.push(e),
+        }
+
+        let mut output = messages(lines);
+        output.push(Output::MinimapUpdated);
+        output
+    }
+
+    /// One exchange of blows between player `id` and the mobile they fight.
+    fn combat_round(&mut self, id: PlayerId) -> Vec<Output> {
+        let Some(player) = self.players.get_mut(&id) else {
+            return Vec::new();
+        };
+        let Some(mobile_id) = player.fighting else {
+            return Vec::new();
+        };
+        let room_key = player.current_location.to_key();
+
+        // The mobile may have died or been left behind since the last round
+        let Some(mobile) = self
+            .mobiles
+            .get_mut(&mobile_id)
+            .filter(|mobile| mobile.location.to_key() == room_key)
+        else {
+            player.fighting = None;
+            return Vec::new();
+        };
+
+        let def = &self.mobile_defs[&mobile.def_id];
+        let player_side = Combatant::for_player(player, &self.objects);
+        let mobile_side = Combatant::for_mobile(def);
+        let (short, verb) = (def.short_description.clone(), def.attack_verb.clone());
+
+        let mut lines = vec![String::new()];
+        match swing(&mut self.rng, &player_side, &mobile_side) {
+            Swing::Hit(damage) => {
+                mobile.hp -= damage;
+                lines.push(format!("You hit {short} for {damage} damage."));
+            }
+            Swing::Miss => lines.push(format!("You miss {short}.")),
+        }
+
+        if mobile.hp <= 0 {
+            lines.extend(self.kill_mobile(mobile_id, id));
+            return messages(lines);
+        }
+
+        match swing(&mut self.rng, &mobile_side, &player_side) {
+            Swing::Hit(damage) => {
+                player.stats.hp -= damage;
+                lines.push(format!(
+                    "{} {verb} you for {damage} damage.",
+                    capitalize(&short)
+                ));
+            }
+            Swing::Miss => lines.push(format!("{} misses you.", capitalize(&short))),
+        }
+
+        if player.stats.hp <= 0 {
+            let mut output = messages(lines);
+            output.extend(self.kill_player(id));
+            return output;
+        }
+
+        messages(lines)
+    }
+
+    /// Removes a slain mobile, ends every fight with it and drops its loot.
+    fn kill_mobile(&mut self, mobile_id: MobileId, killer: PlayerId) -> Vec<String> {
+        let Some(mobile) = self.mobiles.remove(&mobile_id) else {
+            return Vec::new();
+        };
+        for player in self.players.values_mut() {
+            if player.fighting == Some(mobile_id) {
+                player.fighting = None;
+            }
+        }
+
+        let def = &self.mobile_defs[&mobile.def_id];
+        let short = capitalize(&def.short_description);
+        let reward = xp_reward(def.level);
+        let mut lines = vec![format!("{short} is dead! R.I.P.")];
+
+        let room_key = mobile.location.to_key();
+        for entry in &def.loot {
+            let Some(object) = self.objects.get(&entry.object) else {
+                continue;
+            };
+            if !self.rng.gen_bool(entry.chance.clamp(0.0, 1.0)) {
+                continue;
+            }
+            if let Some((room, _)) = self.rooms.get_mut(&room_key) {
+                room.objects.push(entry.object);
+                lines.push(format!("{short} drops {}.", object.short_description));
+            }
+        }
+
+        for line in lines.clone() {
+            self.notify_room(&room_key, killer, line);
+        }
+
+        if let Some(player) = self.players.get_mut(&killer) {
+            lines.extend(player.stats.gain_xp(reward));
+        }
+        lines
+    }
+
+    /// Sends a defeated player back to the start with full health. Everything they
+    /// carried or wore stays behind where they fell.
+    fn kill_player(&mut self, id: PlayerId) -> Vec<Output> {
+        let Some(player) = self.players.get_mut(&id) else {
+            return Vec::new();
+        };
+        let fallen_at = player.current_location.to_key();
+        let mut dropped = std::mem::take(&mut player.inventory);
+        dropped.extend(std::mem::take(&mut player.equipment).into_values());
+        player.stats.restore();
+        player.fighting = None;
+        player.move_to(self.start.clone());
+        let name = player.name.clone();
+
+        if let Some((room, _)) = self.rooms.get_mut(&fallen_at) {
+            room.objects.extend(dropped);
+        }
+        self.notify_room(&fallen_at, id, format!("{name} has been killed!"));
+        self.notify_room(
+            &self.start.to_key(),
+            id,
+            format!("{name} appears, looking shaken."),
+        );
+
+        let mut lines = vec![
+            "You have been KILLED!".to_string(),
+            "You wake up back where your journey began, without your belongings.".to_string(),
+            String::new(),
+        ];
+        lines.extend(self.get_room_display(id));
+        let mut output = messages(lines);
+        output.push(Output::MinimapUpdated);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::{TimeOfDay, MINUTES_PER_HOUR};
+    use crate::room::{Door, Exit};
+
+    fn millhaven() -> World {
+        World::load_from_zones(
+            include_str!("../../rooms/zones.json"),
+            &[
+                ("millhaven.json", include_str!("../../rooms/millhaven.json")),
+                (
+                    "millhaven_mobiles.json",
+                    include_str!("../../rooms/millhaven_mobiles.json"),
+                ),
+            ],
+            include_str!("../../rooms/objects.json"),
+        )
+        .unwrap()
+    }
+
+    fn message(text: &str) -> Output {
+        Output::Message(text.to_string())
+    }
+
+    #[test]
+    fn test_movement_is_broadcast_to_room_occupants() {
+        let mut world = millhaven();
+        let alice = world.add_player("Alice");
+        let bob = world.add_player("Bob");
+        assert_eq!(
+            world.drain_outbox(),
+            vec![(alice, message("Bob has entered the game."))]
+        );
+
+        assert!(world
+            .get_room_display(alice)
+            .contains(&"Bob is here.".to_string()));
+
+        world.handle_command(bob, "north");
+        assert_eq!(
+            world.drain_outbox(),
+            vec![(alice, message("Bob leaves north."))]
+        );
+
+        world.handle_command(alice, "n");
+        assert_eq!(
+            world.drain_outbox(),
+            vec![(bob, message("Alice arrives from the south."))]
+        );
+
+        world.remove_player(bob);
+        assert_eq!(
+            world.drain_outbox(),
+            vec![(alice, message("Bob has left the game."))]
+        );
+        assert!(world.name_available("bob"));
+    }
+
+    #[test]
+    fn test_rooms_have_night_descriptions() {
+        let mut world = millhaven();
+        let alice = world.add_player("Alice");
+        let day = world.get_room_display(alice)[1].clone();
+
+        world.clock.advance_minutes(14 * MINUTES_PER_HOUR);
+        assert_eq!(world.clock.time_of_day(), TimeOfDay::Night);
+        let night = world.get_room_display(alice)[1].clone();
+        assert_ne!(day, night);
+        assert!(night.contains("Moonlight"));
+
+        // Rooms without a night variant keep their usual description
+        world.handle_command(alice, "south");
+        assert!(world.get_room_display(alice)[1].starts_with("This southern stretch"));
+    }
+
+    #[test]
+    fn test_locked_gate_needs_the_key() {
+        let mut world = millhaven();
+        let id = world.add_player("Alice");
+        let mut run = |command: &str| world.handle_command(id, command);
+
+        run("north");
+        run("east");
+        run("south");
+        run("south");
+        assert_eq!(run("east"), vec![message("The gate is closed.")]);
+        assert_eq!(run("open gate"), vec![message("The gate is locked.")]);
+        assert_eq!(run("unlock east"), vec![message("You don't have the key.")]);
+
+        run("north");
+        run("north");
+        run("get key");
+        run("south");
+        run("south");
+        assert_eq!(run("unlock gate"), vec![message("You unlock the gate.")]);
+        assert_eq!(run("open gate"), vec![message("You open the gate.")]);
+        assert_eq!(run("east")[0], message("**Garden Walk**"));
+
+        // The garden side of the gate opened along with it
+        assert_eq!(run("close gate"), vec![message("You close the gate.")]);
+        assert_eq!(run("south"), vec![message("The gate is closed.")]);
+    }
+
+    #[test]
+    fn test_exit_names_win_over_command_prefixes() {
+        let mut world = millhaven();
+        let id = world.add_player("Alice");
+        let start = world.player(id).unwrap().location().to_key();
+        world
+            .rooms
+            .get_mut(&start)
+            .unwrap()
+            .0
+            .exits
+            .insert("in".to_string(), Exit::new("millhaven:7"));
+
+        assert_eq!(
+            world.handle_command(id, "in")[0],
+            message("**The Mystic Emporium**")
+        );
+        assert_eq!(world.player(id).unwrap().location().to_key(), "millhaven:7");
+        // Elsewhere `in` is still short for inventory
+        assert_eq!(
+            world.handle_command(id, "in")[0],
+            message("You are carrying:")
+        );
+    }
+
+    #[test]
+    fn test_travel_walks_the_shortest_route() {
+        let mut world = millhaven();
+        let id = world.add_player("Alice");
+
+        let output = world.handle_command(id, "travel town gate");
+        assert_eq!(
+            output[..3],
+            [
+                message("You go south to South Road."),
+                message("You arrive after 2 steps."),
+                message("**Town Gate**"),
+            ]
+        );
+        assert_eq!(output.last(), Some(&Output::MinimapUpdated));
+        assert_eq!(world.player(id).unwrap().location().to_key(), "millhaven:8");
+
+        assert_eq!(
+            world.handle_command(id, "goto millhaven:8"),
+            vec![message("You are already there.")]
+        );
+        assert_eq!(
+            world.handle_command(id, "travel nowhere"),
+            vec![message("You don't know the way to 'nowhere'.")]
+        );
+    }
+
+    #[test]
+    fn test_speedwalk_stops_at_a_closed_door() {
+        let mut world = millhaven();
+        let id = world.add_player("Alice");
+
+        assert_eq!(
+            world.handle_command(id, "n;e;2s;e"),
+            vec![
+                message("You go north to North Road."),
+                message("You go east to Blacksmith's Forge."),
+                message("You go south to East Road."),
+                message("You go south to Healing House."),
+                message("The gate is closed."),
+                message("You stop after 4 of 5 steps."),
+                Output::MinimapUpdated,
+            ]
+        );
+        assert_eq!(world.player(id).unwrap().location().to_key(), "millhaven:9");
+
+        // Spaces separate steps too
+        let output = world.handle_command(id, "n  north");
+        assert_eq!(output.last(), Some(&Output::MinimapUpdated));
+        assert_eq!(world.player(id).unwrap().location().to_key(), "millhaven:6");
+        assert_eq!(
+            world.handle_command(id, "north nowhere"),
+            vec![message("'north' doesn't take any arguments.")]
+        );
+    }
+
+    #[test]
+    fn test_trading_with_the_blacksmith() {
+        let mut world = millhaven();
+        let id = world.add_player("Alice");
+        let mut run = |command: &str| world.handle_command(id, command);
+
+        assert_eq!(run("list"), vec![message("There is no shopkeeper here.")]);
+        run("north");
+        run("east");
+        assert_eq!(
+            run("list"),
+            vec![
+                message("Ironheart the blacksmith has for sale:"),
+                message("  a short sword - 30 coins"),
+                message("  a leather cap - 10 coins"),
+                message("  a leather jerkin - 24 coins"),
+                message("  a round wooden shield - 14 coins"),
+                message("  an iron horseshoe - 5 coins"),
+            ]
+        );
+        assert_eq!(
+            run("buy sword"),
+            vec![message(
+                "You can't afford a short sword. It costs 30 coins."
+            )]
+        );
+        assert_eq!(
+            run("buy shoe"),
+            vec![message("You buy an iron horseshoe for 5 coins.")]
+        );
+
+        run("get key");
+        assert_eq!(
+            run("value key"),
+            vec![message(
+                "Ironheart the blacksmith isn't interested in a heavy iron key."
+            )]
+        );
+        assert_eq!(
+            run("sell horseshoe"),
+            vec![message("You sell an iron horseshoe for 1 coin.")]
+        );
+        assert_eq!(world.player(id).unwrap().gold(), 16);
+    }
+
+    #[test]
+    fn test_seeded_fight_is_repeatable() {
+        let fight = |seed| {
+            let mut world = millhaven().with_rng_seed(seed);
+            let id = world.add_player("Alice");
+            world.handle_command(id, "south");
+
+            let mut transcript = world.handle_command(id, "consider dog");
+            transcript.extend(world.handle_command(id, "kill dog"));
+            for _ in 0..100 {
+                if world.player(id).is_some_and(|p| p.fighting.is_none()) {
+                    break;
+                }
+                transcript.extend(world.handle_command(id, "kill dog"));
+            }
+            transcript
+        };
+
+        let transcript = fight(42);
+        assert_eq!(transcript, fight(42));
+        assert_eq!(
+            transcript[0],
+            message("The perfect match: you and a stray dog!")
+        );
+        assert!(
+            transcript.contains(&message("A stray dog is dead! R.I.P."))
+                || transcript.contains(&message("You have been KILLED!"))
+        );
+    }
+
+    #[test]
+    fn test_only_actions_and_ticks_bring_combat_rounds() {
+        let mut world = millhaven().with_rng_seed(7);
+        let id = world.add_player("Alice");
+        world.handle_command(id, "south");
+        world.handle_command(id, "kill dog");
+        let dog = world
+            .player(id)
+            .unwrap()
+            .fighting
+            .expect("the fight goes on");
+        let hp = |world: &World| (world.players[&id].stats.hp, world.mobiles[&dog].hp);
+
+        let before = hp(&world);
+        world.handle_command(id, "score");
+        world.handle_command(id, "xyzzy");
+        assert_eq!(hp(&world), before);
+
+        world.drain_outbox();
+        world.tick(Duration::from_secs(2));
+        let swings: Vec<_> = world
+            .drain_outbox()
+            .into_iter()
+            .filter(|(to, output)| {
+                *to == id
+                    && matches!(output, Output::Message(text)
+                        if text.starts_with("You hit") || text.starts_with("You miss"))
+            })
+            .collect();
+        assert_eq!(swings.len(), 1);
+    }
+
+    #[test]
+    fn test_zone_reset_repopulates_only_what_is_missing() {
+        let mut world = millhaven().with_rng_seed(7);
+        let id = world.add_player("Alice");
+        world.players.get_mut(&id).unwrap().stats.hp = 1;
+
+        // One real second is one game minute, and Millhaven resets hourly
+        world.tick(Duration::from_secs(2));
+        assert_eq!(world.player(id).unwrap().stats.hp, 3);
+
+        let square_objects = world.rooms["millhaven:0"].0.objects.clone();
+        world.handle_command(id, "get coin");
+        let rat_home = "millhaven:13";
+        let rat = mobiles_in_room(&world.mobiles, rat_home)[0];
+        world.kill_mobile(rat, id);
+        let gate = |world: &World| world.rooms["millhaven:9"].0.exits["east"].door.clone();
+        let open = gate(&world).map(|door| Door {
+            closed: false,
+            locked: false,
+            ..door
+        });
+        let (room, _) = world.rooms.get_mut("millhaven:9").unwrap();
+        room.exits.get_mut("east").unwrap().door = open;
+        let emporium_west = |world: &World| world.rooms["millhaven:7"].0.exits["west"].hidden;
+        let (room, _) = world.rooms.get_mut("millhaven:7").unwrap();
+        room.exits.get_mut("west").unwrap().hidden = false;
+        let mobile_count = world.mobiles.len();
+
+        world.tick(Duration::from_secs(55));
+        assert!(mobiles_in_room(&world.mobiles, rat_home).is_empty());
+        assert!(!gate(&world).unwrap().closed);
+        assert!(!emporium_west(&world));
+
+        world.tick(Duration::from_secs(5));
+        assert_eq!(mobiles_in_room(&world.mobiles, rat_home).len(), 1);
+        assert_eq!(world.mobiles.len(), mobile_count + 1);
+        assert_eq!(world.rooms["millhaven:0"].0.objects, square_objects);
+        assert_eq!(world.player(id).unwrap().inventory.len(), 1);
+        assert!(gate(&world).unwrap().locked);
+        assert!(world.rooms["millhaven:14"].0.exits["south"].is_closed());
+        assert!(emporium_west(&world));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::clock::ClockConfig;
+use crate::reset::ResetCommand;
+use crate::room::Room;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const ZONES_FILE: &str = "zones.json";
+pub const OBJECTS_FILE: &str = "objects.json";
+pub const RACES_FILE: &str = "races.json";
+pub const CLASSES_FILE: &str = "classes.json";
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ZoneConfig {
+    pub zones: Vec<ZoneInfo>,
+    pub initial_zone: String,
+    pub initial_room: u32,
+    #[serde(default)]
+    pub clock: ClockConfig,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ZoneInfo {
+    pub id: String,
+    pub name: String,
+    pub file: String,
+    /// File of NPC definitions that spawn in this zone.
+    #[serde(default)]
+    pub mobiles: Option<String>,
+    /// Game minutes between resets of the zone; 0 disables resets.
+    #[serde(default = "default_reset_minutes")]
+    pub reset_minutes: u64,
+    #[serde(default)]
+    pub resets: Vec<ResetCommand>,
+}
+
+fn default_reset_minutes() -> u64 {
+    60
+}
+
+pub type RoomMap = HashMap<String, (Room, String)>;
+
+/// The contents of `file`, which zone `zone` points to, from `zone_files`.
+pub fn zone_file<'a>(
+    zone_files: &[(&str, &'a str)],
+    zone: &str,
+    file: &str,
+) -> Result<&'a str, String> {
+    zone_files
+        .iter()
+        .find(|(name, _)| *name == file)
+        .map(|&(_, json_data)| json_data)
+        .ok_or_else(|| format!("zone '{zone}' points to {file}, which was not found"))
+}
+
+pub fn load_rooms(zones: &[ZoneInfo], zone_files: &[(&str, &str)]) -> Result<RoomMap, String> {
+    let mut rooms = HashMap::new();
+
+    for zone_info in zones {
+        let json_data = zone_file(zone_files, &zone_info.id, &zone_info.file)?;
+        let zone_rooms: Vec<Room> =
+            serde_json::from_str(json_data).map_err(|e| format!("{}: {e}", zone_info.file))?;
+        for room in zone_rooms {
+            let key = format!("{}:{}", zone_info.id, room.id);
+            rooms.insert(key, (room, zone_info.id.clone()));
+        }
+    }
+
+    Ok(rooms)
+}
+
+/// Raw zone data read from disk, ready to hand to [`load_rooms`].
+/// `zone_files` holds both room files and mobiles files, keyed by file name.
+pub struct ZoneSource {
+    pub zones_json: String,
+    pub zone_files: Vec<(String, String)>,
+}
+
+impl ZoneSource {
+    pub fn zone_file_refs(&self) -> Vec<(&str, &str)> {
+        self.zone_files
+            .iter()
+            .map(|(file, json)| (file.as_str(), json.as_str()))
+            .collect()
+    }
+}
+
+/// Finds `file` in the content directories. Directories are listed in priority
+/// order, so a file in a later (user override) directory shadows the bundled one.
+pub fn resolve_content_file(content_dirs: &[PathBuf], file: &str) -> Option<PathBuf> {
+    content_dirs
+        .iter()
+        .rev()
+        .map(|dir| dir.join(file))
+        .find(|path| path.is_file())
+}
+
+pub fn read_content_file(content_dirs: &[PathBuf], file: &str) -> Result<String, String> {
+    let path = resolve_content_file(content_dirs, file)
+        .ok_or_else(|| format!("{file} not found in any content directory"))?;
+    read_file(&path)
+}
+
+fn read_file(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+/// Reads `zones.json` and every zone and mobiles file it lists. Files that
+/// cannot be found are left out, matching how [`load_rooms`] treats missing
+/// entries.
+pub fn read_zone_source(content_dirs: &[PathBuf]) -> Result<ZoneSource, String> {
+    let zones_json = read_content_file(content_dirs, ZONES_FILE)?;
+    let zone_config: ZoneConfig =
+        serde_json::from_str(&zones_json).map_err(|e| format!("{ZONES_FILE}: {e}"))?;
+
+    let mut zone_files = Vec::new();
+    for zone_info in &zone_config.zones {
+        for file in std::iter::once(&zone_info.file).chain(&zone_info.mobiles) {
+            if let Some(path) = resolve_content_file(content_dirs, file) {
+                zone_files.push((file.clone(), read_file(&path)?));
+            }
+        }
+    }
+
+    Ok(ZoneSource {
+        zones_json,
+        zone_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_override_dir_shadows_bundled_files() {
+        let root = std::env::temp_dir().join(format!("muddy-zone-test-{}", std::process::id()));
+        let bundled = root.join("bundled");
+        let user = root.join("user");
+        fs::create_dir_all(&bundled).unwrap();
+        fs::create_dir_all(&user).unwrap();
+
+        fs::write(
+            bundled.join(ZONES_FILE),
+            r#"{"zones": [
+                {"id": "town", "name": "Town", "file": "town.json"},
+                {"id": "caves", "name": "Caves", "file": "caves.json"}
+            ], "initial_zone": "town", "initial_room": 0}"#,
+        )
+        .unwrap();
+        fs::write(bundled.join("town.json"), "[]").unwrap();
+        fs::write(
+            user.join("town.json"),
+            r#"[{"id": 0, "name": "Custom", "description": "", "exits": {}, "objects": []}]"#,
+        )
+        .unwrap();
+
+        let source = read_zone_source(&[bundled, user]).unwrap();
+        let zone_config: ZoneConfig = serde_json::from_str(&source.zones_json).unwrap();
+        assert_eq!(source.zone_files.len(), 1);
+        assert_eq!(
+            load_rooms(&zone_config.zones, &source.zone_file_refs()).unwrap_err(),
+            "zone 'caves' points to caves.json, which was not found"
+        );
+
+        let rooms = load_rooms(&zone_config.zones[..1], &source.zone_file_refs()).unwrap();
+        assert_eq!(rooms["town:0"].0.name, "Custom");
+
+        fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,107 @@
+//! Runs Muddy Rogue as a telnet MUD server.
+//!
+//! Usage: `muddy-server [--bind ADDR] [--password PASSWORD] [--tick MILLIS] [CONTENT_DIR]...`
+//!
+//! Every connection gets its own player in one shared world. Content
+//! directories are layered the same way the desktop app loads them.
+
+mod server;
+mod telnet;
+
+use muddy_engine::ticker::DEFAULT_TICK_RATE;
+use muddy_engine::World;
+use server::{serve, ServerConfig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+
+const USAGE: &str =
+    "Usage: muddy-server [--bind ADDR] [--password PASSWORD] [--tick MILLIS] [CONTENT_DIR]...";
+const DEFAULT_BIND: &str = "127.0.0.1:4000";
+
+struct Options {
+    bind: String,
+    password: Option<String>,
+    tick_rate: Duration,
+    content_dirs: Vec<PathBuf>,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        bind: DEFAULT_BIND.to_string(),
+        password: None,
+        tick_rate: DEFAULT_TICK_RATE,
+        content_dirs: Vec::new(),
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bind" => options.bind = args.next().ok_or("--bind needs an address")?,
+            "--password" => {
+                options.password = Some(args.next().ok_or("--password needs a value")?);
+            }
+            "--tick" => {
+                let millis = args.next().ok_or("--tick needs a number of milliseconds")?;
+                let millis: u64 = millis
+                    .parse()
+                    .map_err(|_| format!("'{millis}' is not a number of milliseconds"))?;
+                options.tick_rate = Duration::from_millis(millis);
+            }
+            flag if flag.starts_with('-') => return Err(format!("Unknown option '{flag}'")),
+            dir => options.content_dirs.push(PathBuf::from(dir)),
+        }
+    }
+
+    if options.content_dirs.is_empty() {
+        options.content_dirs.push(PathBuf::from("rooms"));
+    }
+
+    Ok(options)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match parse_args() {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let world = match World::load_from_dirs(&options.content_dirs) {
+        Ok((world, report)) => {
+            if report.warning_count() > 0 {
+                eprintln!("{report}");
+            }
+            world
+        }
+        Err(e) => {
+            eprintln!("Failed to load world: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let listener = match TcpListener::bind(&options.bind).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to listen on {}: {e}", options.bind);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Muddy Rogue listening on {}", options.bind);
+
+    let config = Arc::new(ServerConfig {
+        password: options.password,
+        tick_rate: options.tick_rate,
+    });
+    if let Err(e) = serve(listener, world, config).await {
+        eprintln!("Server stopped: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,396 @@
+use crate::telnet::{encode_text, TelnetCodec, TelnetEvent};
+use muddy_engine::creation::{CharacterCreation, CreationResult};
+use muddy_engine::player::PlayerId;
+use muddy_engine::text_utils::{markup_to_ansi, wrap_lines};
+use muddy_engine::ticker::{run_ticks, TickControl};
+use muddy_engine::{Output, World};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+const DEFAULT_WIDTH: usize = 80;
+const MIN_WIDTH: usize = 20;
+
+pub struct ServerConfig {
+    /// When set, connections must enter this password before playing.
+    pub password: Option<String>,
+    /// Time between world ticks.
+    pub tick_rate: Duration,
+}
+
+/// The world plus a channel to every connected player, so messages one
+/// player causes for another can be delivered.
+struct Shared {
+    world: World,
+    clients: HashMap<PlayerId, UnboundedSender<Output>>,
+}
+
+impl Shared {
+    /// Hands queued world messages to the connections they are meant for.
+    fn deliver_outbox(&mut self) {
+        for (id, output) in self.world.drain_outbox() {
+            if let Some(client) = self.clients.get(&id) {
+                // A closed channel means the player is disconnecting anyway
+                let _ = client.send(output);
+            }
+        }
+    }
+}
+
+type SharedWorld = Arc<Mutex<Shared>>;
+
+/// Accepts telnet connections forever, giving each one its own player in the
+/// shared world.
+pub async fn serve(
+    listener: TcpListener,
+    world: World,
+    config: Arc<ServerConfig>,
+) -> io::Result<()> {
+    let world = Arc::new(Mutex::new(Shared {
+        world,
+        clients: HashMap::new(),
+    }));
+
+    let ticking = Arc::clone(&world);
+    tokio::spawn(run_ticks(
+        TickControl::new(config.tick_rate),
+        move |elapsed| {
+            if let Ok(mut shared) = ticking.lock() {
+                shared.world.tick(elapsed);
+                shared.deliver_outbox();
+            }
+        },
+    ));
+
+    loop {
+        let (stream, address) = listener.accept().await?;
+        let world = Arc::clone(&world);
+        let config = Arc::clone(&config);
+
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(stream, world, config).await {
+                eprintln!("Connection from {address} ended with an error: {e}");
+            }
+        });
+    }
+}
+
+fn lock_world(world: &SharedWorld) -> io::Result<MutexGuard<'_, Shared>> {
+    world
+        .lock()
+        .map_err(|_| io::Error::other("world lock poisoned"))
+}
+
+struct Connection {
+    reader: OwnedReadHalf,
+    writer: OwnedWriteHalf,
+    telnet: TelnetCodec,
+    pending_lines: VecDeque<String>,
+    width: usize,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Self {
+        let (reader, writer) = stream.into_split();
+        Self {
+            reader,
+            writer,
+            telnet: TelnetCodec::new(),
+            pending_lines: VecDeque::new(),
+            width: DEFAULT_WIDTH,
+        }
+    }
+
+    /// Reads the next input line, or `None` once the client disconnects.
+    async fn read_line(&mut self) -> io::Result<Option<String>> {
+        let mut buffer = [0u8; 1024];
+
+        loop {
+            if let Some(line) = self.pending_lines.pop_front() {
+                return Ok(Some(line));
+            }
+
+            let count = self.reader.read(&mut buffer).await?;
+            if count == 0 {
+                return Ok(None);
+            }
+
+            for event in self.telnet.feed(&buffer[..count]) {
+                match event {
+                    TelnetEvent::Line(line) => self.pending_lines.push_back(line),
+                    TelnetEvent::WindowSize { width, .. } => {
+                        self.width = (width as usize).max(MIN_WIDTH);
+                    }
+                }
+            }
+
+            let replies = self.telnet.take_replies();
+            if !replies.is_empty() {
+                self.writer.write_all(&replies).await?;
+            }
+        }
+    }
+
+    async fn send_raw(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.writer.write_all(bytes).await
+    }
+
+    async fn send_line(&mut self, text: &str) -> io::Result<()> {
+        let wrapped = wrap_lines(text, self.width).join("\n");
+        let mut bytes = encode_text(&markup_to_ansi(&wrapped, true));
+        bytes.extend(b"\r\n");
+        self.send_raw(&bytes).await
+    }
+
+    async fn send_outputs(&mut self, outputs: Vec<Output>) -> io::Result<()> {
+        for output in outputs {
+            if let Output::Message(message) = output {
+                self.send_line(&message).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn handle_connection(
+    stream: TcpStream,
+    world: SharedWorld,
+    config: Arc<ServerConfig>,
+) -> io::Result<()> {
+    let mut connection = Connection::new(stream);
+    let start = connection.telnet.start();
+    connection.send_raw(&start).await?;
+
+    if let Some(password) = &config.password {
+        let echo_off = connection.telnet.set_echo_suppressed(true);
+        connection.send_raw(b"Password: ").await?;
+        connection.send_raw(&echo_off).await?;
+
+        let attempt = connection.read_line().await?;
+
+        let echo_on = connection.telnet.set_echo_suppressed(false);
+        connection.send_raw(&echo_on).await?;
+        connection.send_raw(b"\r\n").await?;
+
+        if attempt.as_deref() != Some(password.as_str()) {
+            connection.send_line("Wrong password.").await?;
+            return Ok(());
+        }
+    }
+
+    let Some((player_id, mut inbox)) = log_in(&mut connection, &world).await? else {
+        return Ok(());
+    };
+
+    let result = play(&mut connection, &world, player_id, &mut inbox).await;
+
+    let mut shared = lock_world(&world)?;
+    shared.clients.remove(&player_id);
+    shared.world.remove_player(player_id);
+    shared.deliver_outbox();
+    result
+}
+
+/// Walks the client through character creation, then puts the new
+/// character into the world.
+async fn log_in(
+    connection: &mut Connection,
+    world: &SharedWorld,
+) -> io::Result<Option<(PlayerId, UnboundedReceiver<Output>)>> {
+    let mut creation = CharacterCreation::new();
+    let mut lines = lock_world(world)?.world.creation_prompt(&creation);
+
+    loop {
+        // The last line is the question, asked on the input line itself
+        let question = lines.pop().unwrap_or_default();
+        for line in &lines {
+            connection.send_line(line).await?;
+        }
+        connection
+            .send_raw(format!("{question} ").as_bytes())
+            .await?;
+
+        let Some(line) = connection.read_line().await? else {
+            return Ok(None);
+        };
+
+        let created = {
+            let mut shared = lock_world(world)?;
+            match shared.world.creation_input(&mut creation, &line) {
+                CreationResult::Continue(next) => {
+                    lines = next;
+                    None
+                }
+                CreationResult::Done(character) => {
+                    let (sender, inbox) = mpsc::unbounded_channel();
+                    let player_id = shared.world.add_character(character);
+                    shared.clients.insert(player_id, sender);
+                    shared.deliver_outbox();
+                    Some((player_id, inbox, shared.world.start_messages(player_id)))
+                }
+            }
+        };
+
+        if let Some((player_id, inbox, outputs)) = created {
+            connection.send_outputs(outputs).await?;
+            return Ok(Some((player_id, inbox)));
+        }
+    }
+}
+
+async fn send_prompt(
+    connection: &mut Connection,
+    world: &SharedWorld,
+    player_id: PlayerId,
+) -> io::Result<()> {
+    let prompt = lock_world(world)?.world.prompt(player_id);
+    connection.send_raw(format!("{prompt} ").as_bytes()).await
+}
+
+async fn play(
+    connection: &mut Connection,
+    world: &SharedWorld,
+    player_id: PlayerId,
+    inbox: &mut UnboundedReceiver<Output>,
+) -> io::Result<()> {
+    send_prompt(connection, world, player_id).await?;
+
+    loop {
+        let line = tokio::select! {
+            line = connection.read_line() => line?,
+            Some(output) = inbox.recv() => {
+                // Start a fresh line below the waiting prompt, then redraw it
+                connection.send_raw(b"\r\n").await?;
+                connection.send_outputs(vec![output]).await?;
+                while let Ok(output) = inbox.try_recv() {
+                    connection.send_outputs(vec![output]).await?;
+                }
+                send_prompt(connection, world, player_id).await?;
+                continue;
+            }
+        };
+        let Some(line) = line else {
+            return Ok(());
+        };
+
+        let command = line.trim();
+        if command.eq_ignore_ascii_case("quit") {
+            connection.send_line("Farewell, adventurer.").await?;
+            return Ok(());
+        }
+
+        if !command.is_empty() {
+            let outputs = {
+                let mut shared = lock_world(world)?;
+                let outputs = shared.world.handle_command(player_id, command);
+                shared.deliver_outbox();
+                outputs
+            };
+            connection.send_outputs(outputs).await?;
+        }
+        send_prompt(connection, world, player_id).await?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telnet::{DO, IAC, NAWS, SB, SE, WILL};
+
+    fn millhaven() -> World {
+        World::load_from_zones(
+            include_str!("../../rooms/zones.json"),
+            &[
+                ("millhaven.json", include_str!("../../rooms/millhaven.json")),
+                (
+                    "millhaven_mobiles.json",
+                    include_str!("../../rooms/millhaven_mobiles.json"),
+                ),
+            ],
+            include_str!("../../rooms/objects.json"),
+        )
+        .unwrap()
+    }
+
+    async fn start_server(password: Option<&str>) -> std::net::SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let config = Arc::new(ServerConfig {
+            password: password.map(str::to_string),
+            // Keep ticks out of the way of the scripted sessions
+            tick_rate: Duration::from_secs(3600),
+        });
+        tokio::spawn(serve(listener, millhaven(), config));
+        address
+    }
+
+    /// Reads from the client side until `needle` shows up in the output. Only
+    /// used to wait for the server to go quiet (a prompt), so nothing that
+    /// arrives after the needle is lost.
+    async fn read_until(stream: &mut TcpStream, needle: &[u8]) -> Vec<u8> {
+        let mut received = Vec::new();
+        let mut buffer = [0u8; 1024];
+        while !received.windows(needle.len()).any(|w| w == needle) {
+            let count = stream.read(&mut buffer).await.unwrap();
+            assert!(count > 0, "connection closed before {needle:?} arrived");
+            received.extend(&buffer[..count]);
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_loopback_session_with_password() {
+        let address = start_server(Some("hunter2")).await;
+        let mut client = TcpStream::connect(address).await.unwrap();
+
+        let greeting = read_until(&mut client, &[IAC, WILL, 1]).await;
+        assert!(greeting.starts_with(&[IAC, DO, NAWS]));
+
+        client
+            .write_all(&[IAC, WILL, NAWS, IAC, SB, NAWS, 0, 60, 0, 24, IAC, SE])
+            .await
+            .unwrap();
+        client.write_all(b"hunter2\r\n").await.unwrap();
+        read_until(&mut client, b"known? ").await;
+        client.write_all(b"ann\r\nkeep\r\nyes\r\n").await.unwrap();
+        let welcome = read_until(&mut client, b"> ").await;
+        assert!(String::from_utf8_lossy(&welcome).contains("\x1b[1mTown Square\x1b[0m"));
+
+        client.write_all(b"n\r\n").await.unwrap();
+        let output = read_until(&mut client, b"> ").await;
+        let output = String::from_utf8_lossy(&output);
+        assert!(output.contains("North Road"));
+        assert!(output.lines().all(|line| line.trim_end().len() <= 60 + 8));
+    }
+
+    #[tokio::test]
+    async fn test_players_share_one_world() {
+        let address = start_server(None).await;
+
+        let mut first = TcpStream::connect(address).await.unwrap();
+        read_until(&mut first, b"known? ").await;
+        first.write_all(b"Ann\r\nkeep\r\nyes\r\n").await.unwrap();
+        read_until(&mut first, b"> ").await;
+        first.write_all(b"get coin\r\n").await.unwrap();
+        read_until(&mut first, b"You get a tarnished copper coin.").await;
+
+        let mut second = TcpStream::connect(address).await.unwrap();
+        read_until(&mut second, b"known? ").await;
+        second.write_all(b"ann\r\n").await.unwrap();
+        read_until(&mut second, b"already playing.").await;
+        second.write_all(b"Bob\r\nkeep\r\nyes\r\n").await.unwrap();
+        let welcome = read_until(&mut second, b"> ").await;
+        let welcome = String::from_utf8_lossy(&welcome);
+        assert!(!welcome.contains("copper coin"));
+        assert!(welcome.contains("Ann is here."));
+
+        read_until(&mut first, b"Bob has entered the game.").await;
+        second.write_all(b"n\r\n").await.unwrap();
+        read_until(&mut first, b"Bob leaves north.").await;
+    }
+}
@@ -0,0 +1,239 @@
+//! Minimal telnet protocol handling: strips option negotiation out of the
+//! input stream, assembles lines, and tracks the two options the server
+//! cares about, NAWS (window size) and ECHO (hiding typed passwords).
+
+pub const IAC: u8 = 255;
+pub const DONT: u8 = 254;
+pub const DO: u8 = 253;
+pub const WONT: u8 = 252;
+pub const WILL: u8 = 251;
+pub const SB: u8 = 250;
+pub const SE: u8 = 240;
+
+pub const ECHO: u8 = 1;
+pub const NAWS: u8 = 31;
+
+const MAX_LINE_LEN: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TelnetEvent {
+    Line(String),
+    WindowSize { width: u16, height: u16 },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    Data,
+    Iac,
+    Negotiate(u8),
+    Subnegotiation,
+    SubnegotiationIac,
+}
+
+pub struct TelnetCodec {
+    state: State,
+    line: Vec<u8>,
+    subnegotiation: Vec<u8>,
+    replies: Vec<u8>,
+    naws_requested: bool,
+    echo_suppressed: bool,
+    last_was_cr: bool,
+}
+
+impl TelnetCodec {
+    pub fn new() -> Self {
+        Self {
+            state: State::Data,
+            line: Vec::new(),
+            subnegotiation: Vec::new(),
+            replies: Vec::new(),
+            naws_requested: false,
+            echo_suppressed: false,
+            last_was_cr: false,
+        }
+    }
+
+    /// Negotiation to send when a client connects: ask it to report its size.
+    pub fn start(&mut self) -> Vec<u8> {
+        self.naws_requested = true;
+        vec![IAC, DO, NAWS]
+    }
+
+    /// Claims (or gives back) the echo option. While the server "echoes" but
+    /// sends nothing, clients stop echoing locally, which hides passwords.
+    pub fn set_echo_suppressed(&mut self, suppressed: bool) -> Vec<u8> {
+        self.echo_suppressed = suppressed;
+        vec![IAC, if suppressed { WILL } else { WONT }, ECHO]
+    }
+
+    /// Negotiation replies produced while parsing, to be sent to the client.
+    pub fn take_replies(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.replies)
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<TelnetEvent> {
+        let mut events = Vec::new();
+
+        for &byte in bytes {
+            self.state = match self.state {
+                State::Data => {
+                    if byte == IAC {
+                        State::Iac
+                    } else {
+                        self.push_data(byte, &mut events);
+                        State::Data
+                    }
+                }
+                State::Iac => match byte {
+                    IAC => {
+                        self.push_data(IAC, &mut events);
+                        State::Data
+                    }
+                    WILL | WONT | DO | DONT => State::Negotiate(byte),
+                    SB => {
+                        self.subnegotiation.clear();
+                        State::Subnegotiation
+                    }
+                    // NOP, GA, AYT and friends carry no data
+                    _ => State::Data,
+                },
+                State::Negotiate(command) => {
+                    self.negotiate(command, byte);
+                    State::Data
+                }
+                State::Subnegotiation => {
+                    if byte == IAC {
+                        State::SubnegotiationIac
+                    } else {
+                        self.subnegotiation.push(byte);
+                        State::Subnegotiation
+                    }
+                }
+                State::SubnegotiationIac => match byte {
+                    SE => {
+                        self.finish_subnegotiation(&mut events);
+                        State::Data
+                    }
+                    IAC => {
+                        self.subnegotiation.push(IAC);
+                        State::Subnegotiation
+                    }
+                    _ => State::Subnegotiation,
+                },
+            };
+        }
+
+        events
+    }
+
+    fn push_data(&mut self, byte: u8, events: &mut Vec<TelnetEvent>) {
+        let was_cr = std::mem::replace(&mut self.last_was_cr, byte == b'\r');
+
+        match byte {
+            // CR LF, CR NUL and a bare LF all end a line
+            b'\r' => self.finish_line(events),
+            b'\n' | 0 if was_cr => {}
+            b'\n' => self.finish_line(events),
+            8 | 127 => {
+                self.line.pop();
+            }
+            b'\t' => self.line.push(b' '),
+            byte if byte < 32 => {}
+            byte => {
+                if self.line.len() < MAX_LINE_LEN {
+                    self.line.push(byte);
+                }
+            }
+        }
+    }
+
+    fn finish_line(&mut self, events: &mut Vec<TelnetEvent>) {
+        let line = String::from_utf8_lossy(&self.line).into_owned();
+        self.line.clear();
+        events.push(TelnetEvent::Line(line));
+    }
+
+    fn negotiate(&mut self, command: u8, option: u8) {
+        match (command, option) {
+            (WILL, NAWS) if self.naws_requested => {}
+            (WILL, NAWS) => {
+                self.naws_requested = true;
+                self.replies.extend([IAC, DO, NAWS]);
+            }
+            (WILL, _) => self.replies.extend([IAC, DONT, option]),
+            // An acknowledgement of our own WILL ECHO needs no answer
+            (DO, ECHO) if self.echo_suppressed => {}
+            (DO, _) => self.replies.extend([IAC, WONT, option]),
+            (DONT, ECHO) if self.echo_suppressed => {
+                self.echo_suppressed = false;
+                self.replies.extend([IAC, WONT, ECHO]);
+            }
+            // WONT and DONT for options we never enabled need no answer
+            _ => {}
+        }
+    }
+
+    fn finish_subnegotiation(&mut self, events: &mut Vec<TelnetEvent>) {
+        if let [NAWS, w1, w2, h1, h2, ..] = self.subnegotiation[..] {
+            events.push(TelnetEvent::WindowSize {
+                width: u16::from_be_bytes([w1, w2]),
+                height: u16::from_be_bytes([h1, h2]),
+            });
+        }
+    }
+}
+
+impl Default for TelnetCodec {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Text to send to a telnet client, with line breaks as CR LF. UTF-8 never
+/// contains a 255 byte, so no IAC escaping is needed.
+pub fn encode_text(text: &str) -> Vec<u8> {
+    text.replace('\n', "\r\n").into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strips_negotiation_and_reads_window_size() {
+        let mut codec = TelnetCodec::new();
+        assert_eq!(codec.start(), vec![IAC, DO, NAWS]);
+
+        let mut input = vec![IAC, WILL, NAWS, IAC, SB, NAWS, 0, 120, 0, 40, IAC, SE];
+        input.extend(b"lo");
+        input.extend([IAC, DO, 24]);
+        input.extend(b"ok\r\nn\r\0");
+
+        let events = codec.feed(&input);
+        assert_eq!(
+            events,
+            vec![
+                TelnetEvent::WindowSize {
+                    width: 120,
+                    height: 40
+                },
+                TelnetEvent::Line("look".to_string()),
+                TelnetEvent::Line("n".to_string()),
+            ]
+        );
+        assert_eq!(codec.take_replies(), vec![IAC, WONT, 24]);
+    }
+
+    #[test]
+    fn test_echo_suppression_acknowledgements() {
+        let mut codec = TelnetCodec::new();
+        assert_eq!(codec.set_echo_suppressed(true), vec![IAC, WILL, ECHO]);
+
+        codec.feed(&[IAC, DO, ECHO]);
+        assert!(codec.take_replies().is_empty());
+
+        assert_eq!(codec.set_echo_suppressed(false), vec![IAC, WONT, ECHO]);
+        codec.feed(&[IAC, DO, ECHO]);
+        assert_eq!(codec.take_replies(), vec![IAC, WONT, ECHO]);
+    }
+}
@@ -1,57 +1,30 @@
-mod command_parser;
-mod minimap;
-mod player;
-mod room;
 mod settings;
-mod text_utils;
-mod zone;
 
-use command_parser::{get_room_display, process_move, HELP_TEXT};
-use minimap::{generate_minimap, MinimapNode};
-use player::Player;
+use muddy_engine::minimap::MinimapNode;
+use muddy_engine::pathfinding::RouteStep;
+use muddy_engine::save::{SaveSlotInfo, AUTOSAVE_SLOT, DEFAULT_SLOT};
+use muddy_engine::text_utils;
+use muddy_engine::ticker::{run_ticks, TickControl, DEFAULT_TICK_RATE};
+use muddy_engine::{Game, Output};
 use settings::Settings;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::{AppHandle, Emitter, Manager};
-use zone::{load_rooms, RoomMap, ZoneConfig};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, RunEvent};
 
 struct SettingsState {
     settings: Mutex<Settings>,
     settings_path: PathBuf,
 }
 
-struct Game {
-    rooms: RoomMap,
-    player: Player,
-}
-
-impl Game {
-    fn load_from_zones(
-        zones_json: &str,
-        zone_files: &[(&str, &str)],
-    ) -> Result<Self, serde_json::Error> {
-        let zone_config: ZoneConfig = serde_json::from_str(zones_json)?;
-        let room = load_rooms(&zone_config.zones, zone_files)?;
-
-        Ok(Self {
-            rooms: room,
-            player: Player::new(zone_config.initial_zone, zone_config.initial_room),
-        })
-    }
-
-    fn get_current_room_display(&self) -> Vec<String> {
-        get_room_display(&self.player, &self.rooms)
-    }
-
-    fn process_move(&mut self, command: &str) -> Result<Vec<String>, String> {
-        process_move(&mut self.player, &self.rooms, command)
-    }
-}
-
 struct GameState {
     game: Mutex<Option<Game>>,
 }
 
+struct TickState {
+    control: TickControl,
+}
+
 fn emit_game_message(app: &AppHandle, message: &str) -> Result<(), String> {
     let state = app.state::<SettingsState>();
 
@@ -73,6 +46,34 @@ fn emit_game_message(app: &AppHandle, message: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Forwards engine output to the webview as `stream-message` and
+/// `minimap-update` events.
+fn emit_outputs(app: &AppHandle, outputs: Vec<Output>) {
+    for output in outputs {
+        match output {
+            Output::Message(message) => {
+                let _ = emit_game_message(app, &message);
+            }
+            Output::MinimapUpdated => {
+                let _ = app.emit("minimap-update", ());
+            }
+        }
+    }
+}
+
+/// Sends the player's status prompt (`<20/20hp ...>`) as a game message.
+/// Nothing is sent during character creation, which has no prompt.
+fn emit_prompt(app: &AppHandle) {
+    let state = app.state::<GameState>();
+    let prompt = match state.game.lock() {
+        Ok(game_lock) => game_lock.as_ref().map(Game::prompt),
+        Err(_) => None,
+    };
+    if let Some(prompt) = prompt.filter(|prompt| !prompt.is_empty()) {
+        let _ = emit_game_message(app, &prompt);
+    }
+}
+
 #[tauri::command]
 async fn get_settings(app: AppHandle) -> Result<Settings, String> {
     let state = app.state::<SettingsState>();
@@ -85,6 +86,9 @@ async fn save_settings(app: AppHandle, settings: Settings) -> Result<(), String>
     let state = app.state::<SettingsState>();
     let mut current_settings = state.settings.lock().map_err(|e| e.to_string())?;
     *current_settings = settings.clone();
+    app.state::<TickState>()
+        .control
+        .set_rate(Duration::from_millis(settings.tick_rate_ms));
     settings
         .save_to_path(&state.settings_path)
         .map_err(|e| e.to_string())?;
@@ -96,12 +100,9 @@ async fn send_command(app: AppHandle, command: String) -> Result<(), String> {
     emit_game_message(&app, &format!("> {command}"))?;
 
     tauri::async_runtime::spawn(async move {
-        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-        let responses = process_command(&app, &command);
-        for response in responses {
-            let _ = emit_game_message(&app, &response);
-        }
+        let outputs = process_command(&app, &command);
+        emit_outputs(&app, outputs);
+        emit_prompt(&app);
     });
 
     Ok(())
@@ -110,9 +111,9 @@ async fn send_command(app: AppHandle, command: String) -> Result<(), String> {
 #[tauri::command]
 async fn get_start_message(app: AppHandle) -> Result<(), String> {
     let state = app.state::<GameState>();
-    let messages = if let Ok(game_lock) = state.game.lock() {
+    let outputs = if let Ok(game_lock) = state.game.lock() {
         if let Some(game) = game_lock.as_ref() {
-            game.get_current_room_display()
+            game.start_messages()
         } else {
             Vec::new()
         }
@@ -121,18 +122,26 @@ async fn get_start_message(app: AppHandle) -> Result<(), String> {
     };
 
     tauri::async_runtime::spawn(async move {
-        let _ = emit_game_message(&app, "=== Welcome to Muddy Rogue ===");
-        let _ = emit_game_message(&app, "Type 'help' for available commands.");
-        let _ = emit_game_message(&app, "");
-
-        for message in messages {
-            let _ = emit_game_message(&app, &message);
-        }
+        emit_outputs(&app, outputs);
+        emit_prompt(&app);
     });
 
     Ok(())
 }
 
+/// Stops the world ticking until `resume_world`. Commands still work.
+#[tauri::command]
+async fn pause_world(app: AppHandle) -> Result<(), String> {
+    app.state::<TickState>().control.pause();
+    Ok(())
+}
+
+#[tauri::command]
+async fn resume_world(app: AppHandle) -> Result<(), String> {
+    app.state::<TickState>().control.resume();
+    Ok(())
+}
+
 #[tauri::command]
 async fn get_minimap(app: AppHandle) -> Result<Vec<MinimapNode>, String> {
     let state = app.state::<GameState>();
@@ -142,43 +151,124 @@ async fn get_minimap(app: AppHandle) -> Result<Vec<MinimapNode>, String> {
         .as_ref()
         .ok_or_else(|| "Game not initialized".to_string())?;
 
-    Ok(generate_minimap(
-        &game.player.current_location,
-        &game.rooms,
-        2,
-    ))
+    Ok(game.minimap(2))
+}
+
+/// The minimap `level` storeys above (or, if negative, below) the player,
+/// as reached through up and down exits.
+#[tauri::command]
+async fn get_minimap_level(app: AppHandle, level: i32) -> Result<Vec<MinimapNode>, String> {
+    let state = app.state::<GameState>();
+    let game_lock = state.game.lock().map_err(|e| e.to_string())?;
+
+    let game = game_lock
+        .as_ref()
+        .ok_or_else(|| "Game not initialized".to_string())?;
+
+    Ok(game.minimap_level(2, level))
 }
 
-fn process_command(app: &AppHandle, command: &str) -> Vec<String> {
+/// Every room the player has explored in `zone` (their own zone if not
+/// given), with stubs for unexplored rooms next to them, for a full map view.
+#[tauri::command]
+async fn get_zone_map(app: AppHandle, zone: Option<String>) -> Result<Vec<MinimapNode>, String> {
+    let state = app.state::<GameState>();
+    let game_lock = state.game.lock().map_err(|e| e.to_string())?;
+
+    let game = game_lock
+        .as_ref()
+        .ok_or_else(|| "Game not initialized".to_string())?;
+
+    Ok(game.zone_map(zone.as_deref()))
+}
+
+/// The shortest route from the player's room to the room `target` names,
+/// for highlighting on the minimap.
+#[tauri::command]
+async fn find_route(app: AppHandle, target: String) -> Result<Vec<RouteStep>, String> {
+    let state = app.state::<GameState>();
+    let game_lock = state.game.lock().map_err(|e| e.to_string())?;
+
+    let game = game_lock
+        .as_ref()
+        .ok_or_else(|| "Game not initialized".to_string())?;
+
+    game.route(&target)
+        .ok_or_else(|| format!("No route to '{target}'"))
+}
+
+/// Saves the game and returns the slot it went to, which keeps the case it
+/// was first saved with.
+#[tauri::command]
+async fn save_game(app: AppHandle, slot: Option<String>) -> Result<String, String> {
+    let state = app.state::<GameState>();
+    let game_lock = state.game.lock().map_err(|e| e.to_string())?;
+
+    let game = game_lock
+        .as_ref()
+        .ok_or_else(|| "Game not initialized".to_string())?;
+
+    game.save_to_slot(slot.as_deref().unwrap_or(DEFAULT_SLOT))
+}
+
+#[tauri::command]
+async fn load_game(app: AppHandle, slot: Option<String>) -> Result<(), String> {
+    let messages = {
+        let state = app.state::<GameState>();
+        let mut game_lock = state.game.lock().map_err(|e| e.to_string())?;
+
+        let game = game_lock
+            .as_mut()
+            .ok_or_else(|| "Game not initialized".to_string())?;
+
+        game.restore_from_slot(slot.as_deref().unwrap_or(DEFAULT_SLOT))?;
+        game.get_current_room_display()
+    };
+
+    let _ = app.emit("minimap-update", ());
+    for message in messages {
+        emit_game_message(&app, &message)?;
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn list_saves(app: AppHandle) -> Result<Vec<SaveSlotInfo>, String> {
+    let state = app.state::<GameState>();
+    let game_lock = state.game.lock().map_err(|e| e.to_string())?;
+
+    let game = game_lock
+        .as_ref()
+        .ok_or_else(|| "Game not initialized".to_string())?;
+
+    Ok(game.list_saves())
+}
+
+fn process_command(app: &AppHandle, command: &str) -> Vec<Output> {
     let state = app.state::<GameState>();
     let Ok(mut game_lock) = state.game.lock() else {
-        return vec!["Error: Failed to acquire game lock.".to_string()];
+        return vec![Output::Message(
+            "Error: Failed to acquire game lock.".to_string(),
+        )];
     };
     let Some(game) = game_lock.as_mut() else {
-        return vec!["Error: Game not initialized.".to_string()];
+        return vec![Output::Message("Error: Game not initialized.".to_string())];
     };
 
-    let cmd = command.trim().to_lowercase();
+    game.handle_command(command)
+}
 
-    // Try movement command first
-    if let Ok(messages) = game.process_move(&cmd) {
-        let _ = app.emit("minimap-update", ());
-        return messages;
-    }
+fn tick_game(app: &AppHandle, elapsed: Duration) -> Vec<Output> {
+    let state = app.state::<GameState>();
+    let Ok(mut game_lock) = state.game.lock() else {
+        return Vec::new();
+    };
+    let Some(game) = game_lock.as_mut() else {
+        return Vec::new();
+    };
 
-    // Other commands
-    match cmd.as_str() {
-        "help" => HELP_TEXT.iter().map(|s| s.to_string()).collect(),
-        "look" | "l" => game.get_current_room_display(),
-        "time" => vec![format!(
-            "Current time: {}",
-            chrono::Local::now().format("%H:%M:%S")
-        )],
-        _ => vec![format!(
-            "Unknown command: '{}'. Type 'help' for available commands.",
-            command
-        )],
-    }
+    game.tick(elapsed)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -188,17 +278,44 @@ pub fn run() {
             setup_menu(app)?;
             initialize_game(app)?;
             initialize_settings(app)?;
+            start_world_ticks(app);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             send_command,
             get_start_message,
             get_minimap,
+            get_minimap_level,
+            get_zone_map,
+            find_route,
             get_settings,
             save_settings,
+            save_game,
+            load_game,
+            list_saves,
+            pause_world,
+            resume_world,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                autosave(app);
+            }
+        });
+}
+
+fn autosave(app: &AppHandle) {
+    let state = app.state::<GameState>();
+    let Ok(game_lock) = state.game.lock() else {
+        return;
+    };
+    // Closing during character creation leaves nothing to save
+    if let Some(game) = game_lock.as_ref().filter(|game| game.has_character()) {
+        if let Err(e) = game.save_to_slot(AUTOSAVE_SLOT) {
+            eprintln!("Autosave failed: {e}");
+        }
+    }
 }
 
 fn setup_menu(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
@@ -255,10 +372,42 @@ fn initialize_settings(app: &tauri::App) -> Result<(), Box<dyn std::error::Error
     Ok(())
 }
 
+/// Runs the world tick loop for the life of the app, sending what the
+/// player sees as `stream-message` events.
+fn start_world_ticks(app: &tauri::App) {
+    let rate = match app.state::<SettingsState>().settings.lock() {
+        Ok(settings) => Duration::from_millis(settings.tick_rate_ms),
+        Err(_) => DEFAULT_TICK_RATE,
+    };
+    let control = TickControl::new(rate);
+    app.manage(TickState {
+        control: control.clone(),
+    });
+
+    let app = app.handle().clone();
+    tauri::async_runtime::spawn(run_ticks(control, move |elapsed| {
+        let outputs = tick_game(&app, elapsed);
+        emit_outputs(&app, outputs);
+    }));
+}
+
 fn initialize_game(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let zones_config = include_str!("../rooms/zones.json");
-    let zone_files = [("millhaven.json", include_str!("../rooms/millhaven.json"))];
-    let game = Game::load_from_zones(zones_config, &zone_files)?;
+    // Bundled content first, then the user's override directory on top of it
+    let content_dirs = [
+        app.path().resource_dir()?.join("rooms"),
+        app.path().app_data_dir()?.join("rooms"),
+    ];
+    let saves_dir = app
+        .path()
+        .app_config_dir()
+        .expect("failed to resolve app config dir")
+        .join("saves");
+
+    let (game, report) = Game::load_from_dirs(&content_dirs)?;
+    if report.warning_count() > 0 {
+        eprintln!("{report}");
+    }
+    let game = game.with_saves_dir(saves_dir);
     app.manage(GameState {
         game: Mutex::new(Some(game)),
     });
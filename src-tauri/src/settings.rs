@@ -1,3 +1,4 @@
+use muddy_engine::ticker::DEFAULT_TICK_RATE;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -6,6 +7,13 @@ use std::path::Path;
 pub struct Settings {
     pub word_wrap_enabled: bool,
     pub word_wrap_length: u32,
+    /// Milliseconds between world ticks.
+    #[serde(default = "default_tick_rate_ms")]
+    pub tick_rate_ms: u64,
+}
+
+fn default_tick_rate_ms() -> u64 {
+    DEFAULT_TICK_RATE.as_millis() as u64
 }
 
 impl Default for Settings {
@@ -13,6 +21,7 @@ impl Default for Settings {
         Self {
             word_wrap_enabled: true,
             word_wrap_length: 100,
+            tick_rate_ms: default_tick_rate_ms(),
         }
     }
 }
@@ -9,6 +9,19 @@ interface MiniMapNode {
   room_name: string;
   is_player: boolean;
   connections: string[];
+  up: boolean;
+  down: boolean;
+  other_exits: string[];
+  conflict: boolean;
+  conflicting_connections: string[];
+  stub: boolean;
+}
+
+function stairMarker(node: MiniMapNode): string | null {
+  if (node.up && node.down) return "\u21C5";
+  if (node.up) return "\u25B2";
+  if (node.down) return "\u25BC";
+  return null;
 }
 
 export default function MiniMap({ enabled }: { enabled: boolean }) {
@@ -80,6 +93,10 @@ export default function MiniMap({ enabled }: { enabled: boolean }) {
             // Only draw each line once (from lower key to higher key)
             if (node.room_key > connectedKey) return null;
 
+            // Connections that don't match their direction are dashed
+            const conflicting =
+              node.conflicting_connections.includes(connectedKey);
+
             return (
               <line
                 key={`${node.room_key}-${connectedKey}`}
@@ -89,6 +106,7 @@ export default function MiniMap({ enabled }: { enabled: boolean }) {
                 y2={y2}
                 stroke="#fff"
                 strokeWidth="2"
+                strokeDasharray={conflicting ? "4 3" : undefined}
                 opacity="0.5"
               />
             );
@@ -99,6 +117,7 @@ export default function MiniMap({ enabled }: { enabled: boolean }) {
         {nodes.map((node) => {
           const x = (node.x - minX) * cellSize + cellSize / 2;
           const y = (maxY - node.y) * cellSize + cellSize / 2;
+          const marker = stairMarker(node);
 
           return (
             <g key={node.room_key}>
@@ -107,9 +126,22 @@ export default function MiniMap({ enabled }: { enabled: boolean }) {
                 cy={y}
                 r={node.is_player ? 15 : 10}
                 fill={node.is_player ? "#4CAF50" : "#2196F3"}
-                stroke="#fff"
+                fillOpacity={node.stub ? 0.3 : 1}
+                stroke={node.conflict ? "#FF9800" : "#fff"}
                 strokeWidth="2"
               />
+              {marker && (
+                <text
+                  x={x}
+                  y={y}
+                  textAnchor="middle"
+                  dominantBaseline="central"
+                  fontSize="10"
+                  fill="#fff"
+                >
+                  {marker}
+                </text>
+              )}
             </g>
           );
         })}
@@ -15,6 +15,7 @@ import { invoke } from "@tauri-apps/api/core";
 interface Settings {
   word_wrap_enabled: boolean;
   word_wrap_length: number;
+  tick_rate_ms: number;
 }
 
 interface SettingsDialogProps {
@@ -26,9 +27,11 @@ export default function SettingsDialog({ open, onClose }: SettingsDialogProps) {
   const [settings, setSettings] = useState<Settings>({
     word_wrap_enabled: true,
     word_wrap_length: 100,
+    tick_rate_ms: 2000,
   });
 
   const [wrapLengthInput, setWrapLengthInput] = useState<string>("100");
+  const [tickRateInput, setTickRateInput] = useState<string>("2000");
 
   useEffect(() => {
     if (open) {
@@ -41,6 +44,7 @@ export default function SettingsDialog({ open, onClose }: SettingsDialogProps) {
       const loadedSettings = await invoke<Settings>("get_settings");
       setSettings(loadedSettings);
       setWrapLengthInput(loadedSettings.word_wrap_length.toString());
+      setTickRateInput(loadedSettings.tick_rate_ms.toString());
     } catch (error) {
       console.error("Failed to load settings:", error);
     }
@@ -60,13 +64,29 @@ export default function SettingsDialog({ open, onClose }: SettingsDialogProps) {
     return parsed;
   };
 
+  const validateTickRate = (input: string): number => {
+    const min = 250;
+    const max = 10000;
+    const parsed = parseInt(input);
+
+    if (isNaN(parsed) || parsed < min) {
+      return min;
+    }
+    if (parsed > max) {
+      return max;
+    }
+    return parsed;
+  };
+
   const handleSave = async () => {
     const finalLength = validateLength(wrapLengthInput);
+    const finalTickRate = validateTickRate(tickRateInput);
     try {
       await invoke("save_settings", {
         settings: {
           ...settings,
           word_wrap_length: finalLength,
+          tick_rate_ms: finalTickRate,
         },
       });
       onClose();
@@ -88,6 +108,15 @@ export default function SettingsDialog({ open, onClose }: SettingsDialogProps) {
     setWrapLengthInput(validatedLength.toString());
   };
 
+  const handleTickRateBlur = () => {
+    const validatedRate = validateTickRate(tickRateInput);
+    setSettings((prev) => ({
+      ...prev,
+      tick_rate_ms: validatedRate,
+    }));
+    setTickRateInput(validatedRate.toString());
+  };
+
   return (
     <Dialog open={open} onClose={handleCancel} maxWidth="sm" fullWidth>
       <DialogTitle>Settings</DialogTitle>
@@ -135,6 +164,22 @@ export default function SettingsDialog({ open, onClose }: SettingsDialogProps) {
             }}
             helperText={`Current saved length: ${settings.word_wrap_length}. Enter value between 20-200.`}
           />
+          <TextField
+            fullWidth
+            label="World Tick Rate (ms)"
+            type="number"
+            value={tickRateInput}
+            onChange={(e) => setTickRateInput(e.target.value)}
+            onBlur={handleTickRateBlur}
+            sx={{ mt: 2 }}
+            slotProps={{
+              htmlInput: {
+                min: 250,
+                max: 10000,
+              },
+            }}
+            helperText={`How often the world moves on its own. Current: ${settings.tick_rate_ms} ms. Enter value between 250-10000.`}
+          />
         </Box>
       </DialogContent>
       <DialogActions>